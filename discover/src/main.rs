@@ -1,10 +1,14 @@
 use dotenv::dotenv;
-use lifx::{Client, MessageType, StatePayload};
-use std::convert::TryInto;
+use lifx::discovery::{self, DiscoveryEvent};
+use lifx::{Client, StatePayload, Target};
+use std::collections::HashSet;
 use std::net::UdpSocket;
 use std::sync::Arc;
+use std::time::Duration;
 use storage::Storage;
 
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
   dotenv().ok().unwrap_or_default();
@@ -14,36 +18,53 @@ async fn main() -> anyhow::Result<()> {
 
   let udp_socket = UdpSocket::bind(&addr)?;
   let client = Arc::new(Client::new(1337, udp_socket)?);
+  let storage = Storage::new(&db_url);
 
-  let discoverer = Arc::clone(&client);
-  tokio::spawn(async move {
-    loop {
-      discoverer.get_state().await.unwrap();
-      tokio::time::delay_for(std::time::Duration::from_secs(5)).await;
-    }
-  });
-
-  let receiver = Arc::clone(&client);
-  let storage = Arc::new(tokio::sync::Mutex::new(Storage::new(&db_url)));
-  let task = tokio::spawn(async move {
-    loop {
-      let (_, packet) = receiver.receive_message().await.unwrap();
-      match packet.message_type() {
-        MessageType::State => {
-          let storage = &*storage.lock().await;
-          let state_firmware: StatePayload = packet.try_into().unwrap();
-          let label = String::from_utf8(state_firmware.label.to_vec()).unwrap();
-          if let Some(existing) = storage.get_device_by_label(&label) {
-            storage.update_device(&existing).unwrap();
-          } else {
-            storage.insert_device(storage::NewDevice { label }).unwrap();
-          }
+  let mut watch = discovery::watch(Arc::clone(&client), DISCOVERY_INTERVAL);
+  // known bulbs are re-read every round so changes to them are saved too
+  let mut refresh = tokio::time::interval(DISCOVERY_INTERVAL);
+  let mut known = HashSet::new();
+  loop {
+    tokio::select! {
+      event = watch.recv() => match event {
+        Some(DiscoveryEvent::DeviceAppeared(device)) => {
+          known.insert(device.target());
+          record(&client, &storage, device.target()).await;
+        }
+        Some(DiscoveryEvent::DeviceLost(device)) => {
+          known.remove(&device.target());
+          println!("lost {}", device.target());
+        }
+        None => break,
+      },
+      _ = refresh.tick() => {
+        for target in &known {
+          record(&client, &storage, *target).await;
         }
-        _ => continue,
       }
     }
-  });
+  }
+  Ok(())
+}
+
+/// Saves the bulb's current state. A bulb can drop off between answering
+/// discovery and this request, so failures are reported and watching goes on.
+async fn record(client: &Client, storage: &Storage, target: Target) {
+  let saved = match client.get_state(target).await {
+    Ok(state) => save_state(storage, state),
+    Err(err) => Err(err),
+  };
+  if let Err(err) = saved {
+    eprintln!("unable to record {}: {}", target, err);
+  }
+}
 
-  tokio::join!(task);
+fn save_state(storage: &Storage, state: StatePayload) -> anyhow::Result<()> {
+  let label = state.label.to_string();
+  if let Some(existing) = storage.get_device_by_label(&label) {
+    storage.update_device(&existing)?;
+  } else {
+    storage.insert_device(storage::NewDevice { label })?;
+  }
   Ok(())
 }
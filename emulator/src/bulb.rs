@@ -0,0 +1,128 @@
+use lifx::{
+  Color, GroupPayload, Label, LifxUuid, LocationPayload, MessageType, MultiZoneEffectPayload,
+  Power, Target,
+};
+
+/// Product id of a LIFX A19, the default for emulated bulbs.
+const LIFX_A19: u32 = 27;
+const LIFX_VENDOR: u32 = 1;
+/// Host firmware reported by emulated bulbs unless told otherwise.
+const FIRMWARE: (u16, u16) = (3, 70);
+
+/// A bulb the emulator answers as, along with the state it reports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bulb {
+  pub serial: [u8; 6],
+  pub label: Label,
+  pub vendor: u32,
+  pub product: u32,
+  /// Host firmware version as `(major, minor)`.
+  pub firmware: (u16, u16),
+  pub power: Power,
+  pub color: Color,
+  /// One colour per zone, empty for bulbs without zones.
+  pub zones: Vec<Color>,
+  /// The last effect started across the zones.
+  pub effect: Option<MultiZoneEffectPayload>,
+  /// Rows of pixels of a single tile, empty for bulbs without one.
+  pub tile: Vec<Vec<Color>>,
+  pub group: GroupPayload,
+  pub location: LocationPayload,
+  /// Message types the bulb never answers, as if it didn't understand them.
+  pub ignored: Vec<MessageType>,
+  /// How many more packets addressed to the bulb are lost before it hears one.
+  pub dropped: usize,
+  /// Positions, among the packets the bulb hears, of single packets that get lost instead.
+  pub lost: Vec<usize>,
+  /// Type of every packet the bulb has heard, oldest first.
+  pub received: Vec<MessageType>,
+}
+
+impl Bulb {
+  pub fn new(serial: [u8; 6]) -> Self {
+    Self {
+      serial,
+      label: Label::new("LIFX Bulb"),
+      vendor: LIFX_VENDOR,
+      product: LIFX_A19,
+      firmware: FIRMWARE,
+      power: Power::Off,
+      color: Color::new(0, 0, 100, 3500),
+      zones: vec![],
+      tile: vec![],
+      group: GroupPayload {
+        group: LifxUuid::default(),
+        label: Label::default(),
+        updated_at: 0,
+      },
+      location: LocationPayload {
+        location: LifxUuid::default(),
+        label: Label::default(),
+        updated_at: 0,
+      },
+      ignored: vec![],
+      effect: None,
+      dropped: 0,
+      lost: vec![],
+      received: vec![],
+    }
+  }
+
+  pub fn with_label(mut self, label: impl Into<Label>) -> Self {
+    self.label = label.into();
+    self
+  }
+
+  pub fn with_product(mut self, product: u32) -> Self {
+    self.product = product;
+    self
+  }
+
+  pub fn with_firmware(mut self, major: u16, minor: u16) -> Self {
+    self.firmware = (major, minor);
+    self
+  }
+
+  /// Never answers `message_type`, acknowledgements included.
+  pub fn with_ignored(mut self, message_type: MessageType) -> Self {
+    self.ignored.push(message_type);
+    self
+  }
+
+  /// Loses the first `count` packets addressed to the bulb.
+  pub fn with_dropped(mut self, count: usize) -> Self {
+    self.dropped = count;
+    self
+  }
+
+  /// Loses the packet the bulb would have heard after hearing `index` others.
+  /// A resend of it gets through.
+  pub fn with_lost(mut self, index: usize) -> Self {
+    self.lost.push(index);
+    self
+  }
+
+  /// Gives the bulb `count` zones, all starting out the bulb's colour.
+  pub fn with_zones(mut self, count: usize) -> Self {
+    self.zones = vec![self.color; count];
+    self
+  }
+
+  /// Gives the bulb a single tile of `width` by `height` pixels, all starting out the bulb's colour.
+  pub fn with_tile(mut self, width: usize, height: usize) -> Self {
+    self.tile = vec![vec![self.color; width]; height];
+    self
+  }
+
+  pub fn target(&self) -> Target {
+    Target::Serial(self.serial)
+  }
+
+  /// Paints the whole bulb, every zone included.
+  pub(crate) fn set_color(&mut self, color: Color) {
+    self.color = color;
+    for zone in self.zones.iter_mut() {
+      *zone = color;
+    }
+  }
+}
@@ -0,0 +1,566 @@
+use crate::bulb::Bulb;
+use bytes::Bytes;
+use lifx::{
+  ApplicationRequest, Color, Deserializable, EmptyPayload, FirmwarePayload, Get64Payload,
+  GetColorZonesPayload, Header, IncomingPacket, LabelPayload, Message, MessageType, OutgoingPacket,
+  Serializable, Set64Payload, SetColorZonesPayload, SetExtendedColorZonesPayload, State64Payload,
+  StateExtendedColorZonesPayload, StateMultiZonePayload, StatePayload, StatePowerPayload,
+  StateServicePayload, StateVersionPayload, StateZonePayload, EXTENDED_ZONES, MULTI_ZONES,
+  TILE_COLORS,
+};
+use log::warn;
+use std::convert::TryInto;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::{oneshot, Mutex};
+
+/// Large enough for the biggest message a client sends, a set of extended zones.
+const BUFFER_SIZE: usize = 1024;
+
+const UDP_SERVICE: u8 = 1;
+
+/// Answers LIFX messages on a UDP socket as if it were every one of its bulbs.
+///
+/// The emulator stops once dropped.
+pub struct Emulator {
+  addr: SocketAddr,
+  bulbs: Arc<Mutex<Vec<Bulb>>>,
+  _shutdown: oneshot::Sender<()>,
+}
+
+impl Emulator {
+  pub async fn bind(addr: SocketAddr, bulbs: Vec<Bulb>) -> anyhow::Result<Self> {
+    let socket = UdpSocket::bind(addr).await?;
+    let addr = socket.local_addr()?;
+    let bulbs = Arc::new(Mutex::new(bulbs));
+    let (shutdown, stopped) = oneshot::channel();
+    tokio::spawn(serve(socket, Arc::clone(&bulbs), stopped));
+    Ok(Self {
+      addr,
+      bulbs,
+      _shutdown: shutdown,
+    })
+  }
+
+  /// Where the emulator is listening, useful when bound to port 0.
+  pub fn local_addr(&self) -> SocketAddr {
+    self.addr
+  }
+
+  /// The current state of the bulb with `serial`.
+  pub async fn bulb(&self, serial: [u8; 6]) -> Option<Bulb> {
+    let bulbs = self.bulbs.lock().await;
+    bulbs.iter().find(|bulb| bulb.serial == serial).cloned()
+  }
+
+  pub async fn bulbs(&self) -> Vec<Bulb> {
+    self.bulbs.lock().await.clone()
+  }
+}
+
+async fn serve(
+  mut socket: UdpSocket,
+  bulbs: Arc<Mutex<Vec<Bulb>>>,
+  mut shutdown: oneshot::Receiver<()>,
+) {
+  let port = socket.local_addr().map(|addr| addr.port()).unwrap_or(0);
+  let mut buf = [0; BUFFER_SIZE];
+  loop {
+    let result = tokio::select! {
+      result = socket.recv_from(&mut buf) => result,
+      _ = &mut shutdown => break,
+    };
+    let (amt, addr) = match result {
+      Ok(received) => received,
+      Err(err) => {
+        warn!("unable to read packet: {}", err);
+        continue;
+      }
+    };
+
+    let packet = match IncomingPacket::deserialize(&mut Bytes::copy_from_slice(&buf[..amt])) {
+      Ok(packet) => packet,
+      Err(err) => {
+        warn!("ignoring malformed packet from {}: {}", addr, err);
+        continue;
+      }
+    };
+
+    let mut replies = vec![];
+    for bulb in bulbs.lock().await.iter_mut() {
+      let target = packet.target();
+      if target != 0 && target != u64::from(bulb.target()) {
+        continue;
+      }
+      if bulb.dropped > 0 {
+        bulb.dropped -= 1;
+        continue;
+      }
+      let heard = bulb.received.len();
+      if bulb.lost.contains(&heard) {
+        bulb.lost.retain(|index| *index != heard);
+        continue;
+      }
+      if let Ok(message_type) = packet.message_type() {
+        bulb.received.push(message_type);
+      }
+      if packet
+        .message_type()
+        .is_ok_and(|message_type| bulb.ignored.contains(&message_type))
+      {
+        continue;
+      }
+      match respond(bulb, &packet, port) {
+        Ok(mut packets) => replies.append(&mut packets),
+        Err(err) => warn!("{} unable to answer {}: {}", bulb.target(), addr, err),
+      }
+    }
+
+    for reply in replies {
+      if let Err(err) = send(&mut socket, reply, &addr).await {
+        warn!("unable to reply to {}: {}", addr, err);
+      }
+    }
+  }
+}
+
+async fn send(
+  socket: &mut UdpSocket,
+  packet: OutgoingPacket,
+  addr: &SocketAddr,
+) -> anyhow::Result<()> {
+  let bytes: Vec<u8> = packet.try_into()?;
+  socket.send_to(&bytes, addr).await?;
+  Ok(())
+}
+
+/// Applies `packet` to the bulb and returns what it sends back.
+///
+/// Like real bulbs, a Get is always answered while a Set is only answered
+/// when the sender asked for a response.
+fn respond(
+  bulb: &mut Bulb,
+  packet: &IncomingPacket,
+  port: u16,
+) -> anyhow::Result<Vec<OutgoingPacket>> {
+  let header = packet.header();
+  let mut replies = vec![];
+  if header.ack_required() {
+    replies.push(reply(
+      bulb,
+      header,
+      MessageType::Acknowlegement,
+      EmptyPayload {},
+    )?);
+  }
+  let answer = header.res_required();
+
+  match packet.decode()? {
+    Message::GetService => {
+      let payload = StateServicePayload {
+        service: UDP_SERVICE,
+        port: port as u32,
+      };
+      replies.push(reply(bulb, header, MessageType::StateService, payload)?);
+    }
+    Message::GetVersion => {
+      let payload = StateVersionPayload {
+        vendor: bulb.vendor,
+        product: bulb.product,
+        version: 0,
+      };
+      replies.push(reply(bulb, header, MessageType::StateVersion, payload)?);
+    }
+    Message::GetHostFirmware => {
+      let payload = FirmwarePayload {
+        build: 0,
+        version_minor: bulb.firmware.1,
+        version_major: bulb.firmware.0,
+      };
+      replies.push(reply(
+        bulb,
+        header,
+        MessageType::StateHostFirmware,
+        payload,
+      )?);
+    }
+    Message::GetLabel => replies.push(state_label(bulb, header)?),
+    Message::SetLabel(payload) => {
+      bulb.label = payload.label;
+      if answer {
+        replies.push(state_label(bulb, header)?);
+      }
+    }
+    Message::GetGroup => replies.push(reply(
+      bulb,
+      header,
+      MessageType::StateGroup,
+      bulb.group.clone(),
+    )?),
+    Message::SetGroup(payload) => {
+      bulb.group = payload;
+      if answer {
+        replies.push(reply(
+          bulb,
+          header,
+          MessageType::StateGroup,
+          bulb.group.clone(),
+        )?);
+      }
+    }
+    Message::GetLocation => replies.push(reply(
+      bulb,
+      header,
+      MessageType::StateLocation,
+      bulb.location.clone(),
+    )?),
+    Message::SetLocation(payload) => {
+      bulb.location = payload;
+      if answer {
+        replies.push(reply(
+          bulb,
+          header,
+          MessageType::StateLocation,
+          bulb.location.clone(),
+        )?);
+      }
+    }
+    Message::Get => replies.push(state(bulb, header)?),
+    Message::SetColor(payload) => {
+      bulb.set_color(payload.color);
+      if answer {
+        replies.push(state(bulb, header)?);
+      }
+    }
+    Message::GetPower => replies.push(state_power(bulb, header)?),
+    Message::SetPower(payload) => {
+      bulb.power = payload.level;
+      if answer {
+        replies.push(state_power(bulb, header)?);
+      }
+    }
+    Message::Set64(payload) if !bulb.tile.is_empty() => {
+      set64(bulb, &payload);
+      if answer {
+        let request = Get64Payload {
+          tile_index: payload.tile_index,
+          length: payload.length,
+          x: payload.x,
+          y: payload.y,
+          width: payload.width,
+        };
+        replies.push(state64(bulb, header, &request)?);
+      }
+    }
+    Message::Get64(payload) if !bulb.tile.is_empty() => {
+      replies.push(state64(bulb, header, &payload)?)
+    }
+    // bulbs without zones don't understand zone messages
+    _ if bulb.zones.is_empty() => {}
+    Message::SetColorZones(payload) => {
+      set_color_zones(bulb, &payload);
+      if answer {
+        let request = GetColorZonesPayload {
+          start_index: payload.start_index,
+          end_index: payload.end_index,
+        };
+        replies.append(&mut state_zones(bulb, header, &request)?);
+      }
+    }
+    Message::GetColorZones(payload) => replies.append(&mut state_zones(bulb, header, &payload)?),
+    Message::SetExtendedColorZones(payload) => {
+      set_extended_color_zones(bulb, &payload);
+      if answer {
+        replies.append(&mut state_extended_zones(bulb, header)?);
+      }
+    }
+    Message::GetExtendedColorZones => replies.append(&mut state_extended_zones(bulb, header)?),
+    Message::SetMultiZoneEffect(payload) => bulb.effect = Some(payload),
+    _ => {}
+  }
+  Ok(replies)
+}
+
+fn reply(
+  bulb: &Bulb,
+  header: &Header,
+  message_type: MessageType,
+  payload: impl Serializable,
+) -> anyhow::Result<OutgoingPacket> {
+  OutgoingPacket::builder(message_type)
+    .sequence(header.sequence())
+    .source(header.source())
+    .target(bulb.target().into())
+    .build(payload)
+}
+
+fn state(bulb: &Bulb, header: &Header) -> anyhow::Result<OutgoingPacket> {
+  let payload = StatePayload {
+    color: bulb.color,
+    power: bulb.power.into(),
+    label: bulb.label.clone(),
+  };
+  reply(bulb, header, MessageType::State, payload)
+}
+
+fn state_label(bulb: &Bulb, header: &Header) -> anyhow::Result<OutgoingPacket> {
+  let payload = LabelPayload::new(bulb.label.clone());
+  reply(bulb, header, MessageType::StateLabel, payload)
+}
+
+fn state_power(bulb: &Bulb, header: &Header) -> anyhow::Result<OutgoingPacket> {
+  let payload = StatePowerPayload { level: bulb.power };
+  reply(bulb, header, MessageType::StatePower, payload)
+}
+
+/// Pixels of the tile `index` places into a block `width` wide, starting at `x`, `y`.
+fn tile_pixel(bulb: &Bulb, x: u8, y: u8, width: u8, index: usize) -> Option<(usize, usize)> {
+  let width = (width as usize).max(1);
+  let row = y as usize + index / width;
+  let column = x as usize + index % width;
+  let in_tile = row < bulb.tile.len() && column < bulb.tile[row].len();
+  in_tile.then_some((row, column))
+}
+
+fn set64(bulb: &mut Bulb, payload: &Set64Payload) {
+  for (index, color) in payload.colors.iter().enumerate() {
+    if let Some((row, column)) = tile_pixel(bulb, payload.x, payload.y, payload.width, index) {
+      bulb.tile[row][column] = *color;
+    }
+  }
+}
+
+fn state64(bulb: &Bulb, header: &Header, request: &Get64Payload) -> anyhow::Result<OutgoingPacket> {
+  let colors = (0..TILE_COLORS)
+    .map(|index| {
+      tile_pixel(bulb, request.x, request.y, request.width, index)
+        .map_or(Color::from_raw(0, 0, 0, 0), |(row, column)| {
+          bulb.tile[row][column]
+        })
+    })
+    .collect();
+  let payload = State64Payload {
+    tile_index: request.tile_index,
+    x: request.x,
+    y: request.y,
+    width: request.width,
+    colors,
+  };
+  reply(bulb, header, MessageType::State64, payload)
+}
+
+/// Zone changes apply straight away, the emulator doesn't buffer `NoApply` requests.
+fn set_color_zones(bulb: &mut Bulb, payload: &SetColorZonesPayload) {
+  if payload.apply == ApplicationRequest::ApplyOnly {
+    return;
+  }
+  let end = (payload.end_index as usize).min(bulb.zones.len() - 1);
+  for zone in (payload.start_index as usize)..=end {
+    bulb.zones[zone] = payload.color;
+  }
+}
+
+fn set_extended_color_zones(bulb: &mut Bulb, payload: &SetExtendedColorZonesPayload) {
+  let start = payload.zone_index as usize;
+  for (zone, color) in bulb.zones.iter_mut().skip(start).zip(payload.colors.iter()) {
+    *zone = *color;
+  }
+}
+
+/// Answers with a `StateZone` for a single zone, otherwise a `StateMultiZone` per eight zones.
+fn state_zones(
+  bulb: &Bulb,
+  header: &Header,
+  request: &GetColorZonesPayload,
+) -> anyhow::Result<Vec<OutgoingPacket>> {
+  let zones_count = bulb.zones.len() as u8;
+  let start = request.start_index as usize;
+  let end = (request.end_index as usize).min(bulb.zones.len() - 1);
+  if start > end {
+    return Ok(vec![]);
+  }
+
+  if start == end {
+    let payload = StateZonePayload {
+      zones_count,
+      zone_index: start as u8,
+      color: bulb.zones[start],
+    };
+    return Ok(vec![reply(bulb, header, MessageType::StateZone, payload)?]);
+  }
+
+  let mut replies = vec![];
+  for zone_index in (start..=end).step_by(MULTI_ZONES) {
+    let mut colors = [Color::new(0, 0, 0, 0); MULTI_ZONES];
+    for (color, zone) in colors.iter_mut().zip(bulb.zones.iter().skip(zone_index)) {
+      *color = *zone;
+    }
+    let payload = StateMultiZonePayload {
+      zones_count,
+      zone_index: zone_index as u8,
+      colors,
+    };
+    replies.push(reply(bulb, header, MessageType::StateMultiZone, payload)?);
+  }
+  Ok(replies)
+}
+
+fn state_extended_zones(bulb: &Bulb, header: &Header) -> anyhow::Result<Vec<OutgoingPacket>> {
+  let mut replies = vec![];
+  for (chunk, colors) in bulb.zones.chunks(EXTENDED_ZONES).enumerate() {
+    let payload = StateExtendedColorZonesPayload {
+      zones_count: bulb.zones.len() as u16,
+      zone_index: (chunk * EXTENDED_ZONES) as u16,
+      colors: colors.to_vec(),
+    };
+    replies.push(reply(
+      bulb,
+      header,
+      MessageType::StateExtendedColorZones,
+      payload,
+    )?);
+  }
+  Ok(replies)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use lifx::{Power, SetColorPayload, SetPowerPayload};
+  use std::time::Duration;
+
+  const KITCHEN: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 1];
+  const HALLWAY: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 2];
+
+  async fn emulator() -> Emulator {
+    let bulbs = vec![
+      Bulb::new(KITCHEN).with_label("Kitchen"),
+      Bulb::new(HALLWAY).with_label("Hallway").with_zones(16),
+    ];
+    Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+      .await
+      .unwrap()
+  }
+
+  async fn exchange(
+    emulator: &Emulator,
+    packet: OutgoingPacket,
+    expected: usize,
+  ) -> Vec<IncomingPacket> {
+    let mut socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    send(&mut socket, packet, &emulator.local_addr())
+      .await
+      .unwrap();
+
+    let mut replies = vec![];
+    let mut buf = [0; BUFFER_SIZE];
+    for _ in 0..expected {
+      let (amt, _) = tokio::time::timeout(Duration::from_secs(1), socket.recv_from(&mut buf))
+        .await
+        .expect("timed out waiting for a reply")
+        .unwrap();
+      let mut bytes = Bytes::copy_from_slice(&buf[..amt]);
+      replies.push(IncomingPacket::deserialize(&mut bytes).unwrap());
+    }
+    replies
+  }
+
+  #[tokio::test]
+  async fn should_answer_get_service_for_every_bulb() {
+    let emulator = emulator().await;
+    let packet = OutgoingPacket::builder(MessageType::GetService)
+      .source(1337)
+      .build(EmptyPayload {})
+      .unwrap();
+    let replies = exchange(&emulator, packet, 2).await;
+
+    let mut targets: Vec<u64> = replies.iter().map(|packet| packet.target()).collect();
+    targets.sort_unstable();
+    assert_eq!(
+      targets,
+      vec![
+        u64::from(Bulb::new(KITCHEN).target()),
+        u64::from(Bulb::new(HALLWAY).target())
+      ]
+    );
+    for packet in replies {
+      assert_eq!(packet.source(), 1337);
+      match packet.decode().unwrap() {
+        Message::StateService(service) => {
+          assert_eq!(service.service, UDP_SERVICE);
+          assert_eq!(service.port, emulator.local_addr().port() as u32);
+        }
+        _ => panic!("expected StateService"),
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn should_acknowledge_and_apply_set_color() {
+    let emulator = emulator().await;
+    let color = Color::new(120, 100, 50, 3500);
+    let packet = OutgoingPacket::builder(MessageType::SetColor)
+      .sequence(9)
+      .target(Bulb::new(HALLWAY).target().into())
+      .ack_required(true)
+      .build(SetColorPayload { color, duration: 0 })
+      .unwrap();
+    let replies = exchange(&emulator, packet, 1).await;
+
+    assert_eq!(replies[0].message_type(), Ok(MessageType::Acknowlegement));
+    assert_eq!(replies[0].sequence(), 9);
+    let hallway = emulator.bulb(HALLWAY).await.unwrap();
+    assert_eq!(hallway.color, color);
+    assert!(hallway.zones.iter().all(|zone| *zone == color));
+    assert_ne!(emulator.bulb(KITCHEN).await.unwrap().color, color);
+  }
+
+  #[tokio::test]
+  async fn should_report_state_of_targeted_bulb() {
+    let emulator = emulator().await;
+    let packet = OutgoingPacket::builder(MessageType::SetPower)
+      .target(Bulb::new(KITCHEN).target().into())
+      .res_required(true)
+      .build(SetPowerPayload {
+        level: Power::On,
+        duration: 0,
+      })
+      .unwrap();
+    let replies = exchange(&emulator, packet, 1).await;
+    match replies[0].decode().unwrap() {
+      Message::StatePower(payload) => assert_eq!(payload.level, Power::On),
+      _ => panic!("expected StatePower"),
+    }
+
+    let packet = OutgoingPacket::builder(MessageType::Get)
+      .target(Bulb::new(KITCHEN).target().into())
+      .build(EmptyPayload {})
+      .unwrap();
+    let replies = exchange(&emulator, packet, 1).await;
+    match replies[0].decode().unwrap() {
+      Message::State(state) => {
+        assert_eq!(state.label.as_str(), "Kitchen");
+        assert_eq!(state.power, u16::from(Power::On));
+      }
+      _ => panic!("expected State"),
+    }
+  }
+
+  #[tokio::test]
+  async fn should_report_extended_zones() {
+    let emulator = emulator().await;
+    let packet = OutgoingPacket::builder(MessageType::GetExtendedColorZones)
+      .build(EmptyPayload {})
+      .unwrap();
+    // only the hallway has zones
+    let replies = exchange(&emulator, packet, 1).await;
+    match replies[0].decode().unwrap() {
+      Message::StateExtendedColorZones(payload) => {
+        assert_eq!(payload.zones_count, 16);
+        assert_eq!(payload.colors.len(), 16);
+      }
+      _ => panic!("expected StateExtendedColorZones"),
+    }
+  }
+}
@@ -0,0 +1,7 @@
+//! Impersonates LIFX bulbs on a UDP socket, so clients can be tested without hardware.
+
+mod bulb;
+mod emulator;
+
+pub use bulb::Bulb;
+pub use emulator::Emulator;
@@ -0,0 +1,435 @@
+use emulator::{Bulb, Emulator};
+use lifx::{
+  discovery, Client, ClientConfig, Color, MessageType, MoveDirection, MultiZoneEffect, Power,
+  RetryPolicy, Target, TimeoutError, UnsupportedError,
+};
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+const KITCHEN: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 1];
+const HALLWAY: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 2];
+const PORCH: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 3];
+const STAIRS: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 0, 4];
+
+const LIFX_Z: u32 = 32;
+const LIFX_BEAM: u32 = 38;
+const LIFX_CANDLE_WHITE_TO_WARM: u32 = 81;
+const LIFX_TILE: u32 = 55;
+
+async fn emulator() -> Emulator {
+  let bulbs = vec![
+    Bulb::new(KITCHEN).with_label("Kitchen"),
+    Bulb::new(HALLWAY)
+      .with_label("Hallway")
+      .with_product(LIFX_BEAM)
+      .with_zones(100),
+    Bulb::new(PORCH)
+      .with_label("Porch")
+      .with_product(LIFX_CANDLE_WHITE_TO_WARM),
+    // firmware from before the extended zone messages
+    Bulb::new(STAIRS)
+      .with_label("Stairs")
+      .with_product(LIFX_Z)
+      .with_firmware(2, 60)
+      .with_zones(24),
+  ];
+  Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap()
+}
+
+/// A client that reaches the emulator as a unicast peer.
+fn client(emulator: &Emulator) -> Client {
+  let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let config = ClientConfig::new(vec![], lifx::PORT, vec![emulator.local_addr()]);
+  Client::new(1337, socket).unwrap().with_config(config)
+}
+
+#[tokio::test]
+async fn should_discover_peers() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+
+  let mut devices = discovery::discover(&client, Duration::from_millis(200))
+    .await
+    .unwrap();
+  devices.sort_by_key(|device| device.serial);
+
+  assert_eq!(devices.len(), 4);
+  assert_eq!(devices[0].serial, KITCHEN);
+  assert_eq!(devices[1].serial, HALLWAY);
+  assert_eq!(devices[0].port, emulator.local_addr().port() as u32);
+}
+
+#[tokio::test]
+async fn should_broadcast_to_configured_port() {
+  let emulator = emulator().await;
+  let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let config = ClientConfig::new(
+    vec!["127.0.0.1".parse().unwrap()],
+    emulator.local_addr().port(),
+    vec![],
+  );
+  let client = Client::new(1337, socket).unwrap().with_config(config);
+
+  let state = client.get_state(Target::Serial(KITCHEN)).await.unwrap();
+  assert_eq!(state.label.as_str(), "Kitchen");
+}
+
+#[tokio::test]
+async fn should_change_power_and_color() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(KITCHEN);
+  let color = Color::new(240, 100, 80, 3500);
+
+  client.set_power(target, Power::On, 0).await.unwrap();
+  client.set_color(target, color, 0).await.unwrap();
+
+  let state = client.get_state(target).await.unwrap();
+  assert_eq!(state.power, u16::from(Power::On));
+  assert_eq!(state.color, color);
+  assert_eq!(emulator.bulb(KITCHEN).await.unwrap().color, color);
+  assert_eq!(emulator.bulb(HALLWAY).await.unwrap().power, Power::Off);
+}
+
+#[tokio::test]
+async fn should_rename_bulb() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(HALLWAY);
+
+  client.set_label(target, "Landing").await.unwrap();
+
+  let label = client.get_label(target).await.unwrap();
+  assert_eq!(label.label.as_str(), "Landing");
+}
+
+#[tokio::test]
+async fn should_read_and_write_zones() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(HALLWAY);
+  let colors: Vec<Color> = (0..100)
+    .map(|zone| Color::new(zone * 3, 100, 100, 3500))
+    .collect();
+
+  client.set_zones(target, &colors, 0).await.unwrap();
+
+  assert_eq!(client.get_zones(target).await.unwrap(), colors);
+}
+
+#[tokio::test]
+async fn should_refuse_unsupported_commands() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(KITCHEN);
+
+  let capabilities = client.capabilities(target).await.unwrap().unwrap();
+  assert_eq!(capabilities.name(), "LIFX A19");
+  assert!(capabilities.features.color);
+
+  let err = client.set_infrared(target, 50).await.unwrap_err();
+  assert_eq!(
+    err.downcast_ref::<UnsupportedError>(),
+    Some(&UnsupportedError {
+      target,
+      product: "LIFX A19",
+      feature: "infrared",
+    })
+  );
+  assert!(client.get_zones(target).await.is_err());
+}
+
+#[tokio::test]
+async fn should_downgrade_color_on_white_bulbs() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(PORCH);
+
+  let red = Color::from_raw(0, 65535, 32768, 9000);
+  client.set_color(target, red, 0).await.unwrap();
+
+  let color = emulator.bulb(PORCH).await.unwrap().color;
+  assert_eq!(color, Color::from_raw(0, 0, 32768, 6500));
+}
+
+#[tokio::test]
+async fn should_reject_reading_zones_from_every_device() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+
+  assert!(client.get_zones(Target::All).await.is_err());
+}
+
+#[tokio::test]
+async fn should_fall_back_to_legacy_zone_messages() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(STAIRS);
+  let capabilities = client.capabilities(target).await.unwrap().unwrap();
+  assert!(!capabilities.features.extended_multizone);
+
+  let colors: Vec<Color> = (0..24)
+    .map(|zone| Color::new(zone * 15, 100, 100, 3500))
+    .collect();
+  client.set_zones(target, &colors, 0).await.unwrap();
+
+  assert_eq!(emulator.bulb(STAIRS).await.unwrap().zones, colors);
+  assert_eq!(client.get_zones(target).await.unwrap(), colors);
+}
+
+#[tokio::test]
+async fn should_merge_runs_of_legacy_zones() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(STAIRS);
+  let red = Color::new(0, 100, 100, 3500);
+  let blue = Color::new(240, 100, 100, 3500);
+  let mut colors = vec![red; 10];
+  colors.extend(vec![blue; 10]);
+  colors.extend(vec![red; 4]);
+
+  client.set_zones(target, &colors, 0).await.unwrap();
+
+  let bulb = emulator.bulb(STAIRS).await.unwrap();
+  assert_eq!(bulb.zones, colors);
+  let messages = bulb
+    .received
+    .iter()
+    .filter(|message_type| **message_type == MessageType::SetColorZones)
+    .count();
+  assert_eq!(messages, 3);
+}
+
+#[tokio::test]
+async fn should_resend_lost_legacy_zone_ranges() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  // the version and firmware lookups come first, then one message per range
+  let bulbs = vec![Bulb::new(serial)
+    .with_product(LIFX_Z)
+    .with_firmware(2, 60)
+    .with_zones(24)
+    .with_lost(3)];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+  let client = client(&emulator);
+  let red = Color::new(0, 100, 100, 3500);
+  let blue = Color::new(240, 100, 100, 3500);
+  let mut colors = vec![red; 10];
+  colors.extend(vec![blue; 10]);
+  colors.extend(vec![red; 4]);
+
+  client
+    .set_zones(Target::Serial(serial), &colors, 0)
+    .await
+    .unwrap();
+
+  let bulb = emulator.bulb(serial).await.unwrap();
+  assert_eq!(bulb.zones, colors);
+  assert_eq!(
+    &bulb.received[2..],
+    &[
+      MessageType::SetColorZones,
+      MessageType::SetColorZones,
+      MessageType::SetColorZones,
+    ]
+  );
+  assert!(bulb.lost.is_empty());
+}
+
+#[tokio::test]
+async fn should_give_every_effect_its_own_id() {
+  let emulator = emulator().await;
+  let client = client(&emulator);
+  let target = Target::Serial(HALLWAY);
+
+  let mut ids = vec![];
+  for _ in 0..3 {
+    let effect = MultiZoneEffect::Move(MoveDirection::Right);
+    client
+      .start_multizone_effect(target, effect, 1000, 0)
+      .await
+      .unwrap();
+    let bulb = emulator.bulb(HALLWAY).await.unwrap();
+    ids.push(bulb.effect.unwrap().instance_id);
+  }
+  ids.dedup();
+  assert_eq!(ids.len(), 3);
+}
+
+#[tokio::test]
+async fn should_send_commands_when_capabilities_are_unknown() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let bulbs = vec![Bulb::new(serial).with_ignored(MessageType::GetVersion)];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+  let policy = RetryPolicy::new(2, Duration::from_millis(20), Duration::from_millis(100));
+  let client = client(&emulator).with_retry_policy(policy);
+  let target = Target::Serial(serial);
+  let color = Color::new(120, 100, 100, 3500);
+
+  client.set_color(target, color, 0).await.unwrap();
+  assert_eq!(emulator.bulb(serial).await.unwrap().color, color);
+  // the failed lookup isn't retried on every command
+  assert_eq!(client.capabilities(target).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn should_not_hold_commands_on_a_slow_lookup() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let bulbs = vec![Bulb::new(serial)
+    .with_ignored(MessageType::GetVersion)
+    .with_ignored(MessageType::GetHostFirmware)];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+  let client = client(&emulator);
+  let color = Color::new(120, 100, 100, 3500);
+
+  let started = std::time::Instant::now();
+  client
+    .set_color(Target::Serial(serial), color, 0)
+    .await
+    .unwrap();
+  // one short attempt at both lookups together, well inside a single retry deadline
+  assert!(started.elapsed() < Duration::from_secs(1));
+  assert_eq!(emulator.bulb(serial).await.unwrap().color, color);
+}
+
+#[tokio::test]
+async fn should_stop_watching_once_dropped() {
+  let emulator = emulator().await;
+  let client = Arc::new(client(&emulator));
+
+  let mut watch = discovery::watch(Arc::clone(&client), Duration::from_millis(50));
+  for _ in 0..4 {
+    watch.recv().await.unwrap();
+  }
+  // let a few rounds pass with nothing new to report
+  tokio::time::delay_for(Duration::from_millis(200)).await;
+  drop(watch);
+
+  tokio::time::delay_for(Duration::from_millis(100)).await;
+  assert_eq!(Arc::strong_count(&client), 1);
+}
+
+#[tokio::test]
+async fn should_resend_until_acknowledged() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let bulbs = vec![Bulb::new(serial).with_dropped(2)];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+  let policy = RetryPolicy::new(3, Duration::from_millis(20), Duration::from_secs(1));
+  let client = client(&emulator).with_retry_policy(policy);
+
+  client
+    .set_power(Target::Serial(serial), Power::On, 0)
+    .await
+    .unwrap();
+  assert_eq!(emulator.bulb(serial).await.unwrap().power, Power::On);
+}
+
+#[tokio::test]
+async fn should_time_out_once_attempts_run_out() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let bulbs = vec![Bulb::new(serial).with_ignored(MessageType::SetPower)];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+  let policy = RetryPolicy::new(3, Duration::from_millis(20), Duration::from_secs(1));
+  let client = client(&emulator).with_retry_policy(policy);
+
+  let err = client
+    .set_power(Target::Serial(serial), Power::On, 0)
+    .await
+    .unwrap_err();
+  let timeout = err.downcast_ref::<TimeoutError>().unwrap();
+  assert_eq!(timeout.message_type, MessageType::SetPower);
+  assert_eq!(timeout.attempts, policy.attempts);
+  assert!(timeout.elapsed >= Duration::from_millis(140));
+  assert!(timeout.elapsed < policy.deadline);
+}
+
+async fn tile_emulator(serial: [u8; 6], width: usize, height: usize) -> Emulator {
+  let bulbs = vec![Bulb::new(serial)
+    .with_product(LIFX_TILE)
+    .with_tile(width, height)];
+  Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn should_split_large_tile_frames() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let emulator = tile_emulator(serial, 16, 8).await;
+  let client = client(&emulator);
+  let target = Target::Serial(serial);
+  let frame: Vec<Vec<Color>> = (0..8)
+    .map(|y| {
+      (0..16)
+        .map(|x| Color::from_raw(y * 4096 + x * 256, 65535, 65535, 3500))
+        .collect()
+    })
+    .collect();
+
+  client.set_tile_frame(target, 0, &frame, 0).await.unwrap();
+
+  assert_eq!(
+    client.get_tile_frame(target, 0, 16, 8).await.unwrap(),
+    frame
+  );
+  assert_eq!(emulator.bulb(serial).await.unwrap().tile, frame);
+}
+
+#[tokio::test]
+async fn should_send_tile_frames_without_asking_for_replies() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let emulator = tile_emulator(serial, 16, 8).await;
+  let client = client(&emulator);
+  let target = Target::Serial(serial);
+  let mut unsolicited = client.subscribe();
+  let frame = vec![vec![Color::from_raw(0, 0, 65535, 3500); 16]; 8];
+
+  let (sent, state) = tokio::join!(
+    client.set_tile_frame(target, 0, &frame, 0),
+    client.get_state(target)
+  );
+  sent.unwrap();
+  assert_eq!(state.unwrap().power, u16::from(Power::Off));
+
+  let quiet = tokio::time::timeout(Duration::from_millis(200), async {
+    loop {
+      let (_, packet) = unsolicited.recv().await.unwrap();
+      if packet.message_type() == Ok(MessageType::State64) {
+        return packet;
+      }
+    }
+  });
+  assert!(quiet.await.is_err());
+  assert_eq!(emulator.bulb(serial).await.unwrap().tile, frame);
+}
+
+#[tokio::test]
+async fn should_reject_tile_frames_that_cannot_be_addressed() {
+  let serial = [0xd0, 0x73, 0xd5, 0, 0, 9];
+  let emulator = tile_emulator(serial, 8, 8).await;
+  let client = client(&emulator);
+  let target = Target::Serial(serial);
+  let color = Color::from_raw(0, 0, 65535, 3500);
+
+  let too_wide = vec![vec![color; 65]; 1];
+  let err = client.set_tile_frame(target, 0, &too_wide, 0).await;
+  assert!(err.unwrap_err().to_string().contains("65"));
+
+  let too_tall = vec![vec![color; 1]; 256];
+  let err = client.set_tile_frame(target, 0, &too_tall, 0).await;
+  assert!(err.unwrap_err().to_string().contains("256"));
+
+  assert!(client.get_tile_frame(target, 0, 65, 1).await.is_err());
+}
@@ -0,0 +1,76 @@
+use emulator::{Bulb, Emulator};
+use lifx::{discovery, Client, ClientConfig, Color, Light, Power};
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+const DESK: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 1, 1];
+const SHELF: [u8; 6] = [0xd0, 0x73, 0xd5, 0, 1, 2];
+
+async fn lights() -> (Emulator, Vec<Light>) {
+  let bulbs = vec![
+    Bulb::new(DESK).with_label("Desk"),
+    Bulb::new(SHELF).with_label("Shelf"),
+  ];
+  let emulator = Emulator::bind("127.0.0.1:0".parse().unwrap(), bulbs)
+    .await
+    .unwrap();
+
+  let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+  let config = ClientConfig::new(vec![], lifx::PORT, vec![emulator.local_addr()]);
+  let client = Arc::new(Client::new(1337, socket).unwrap().with_config(config));
+
+  let mut lights = discovery::lights(&client, Duration::from_millis(200))
+    .await
+    .unwrap();
+  lights.sort_by_key(Light::serial);
+  (emulator, lights)
+}
+
+#[tokio::test]
+async fn should_connect_discovered_lights() {
+  let (emulator, lights) = lights().await;
+
+  assert_eq!(lights.len(), 2);
+  let desk = &lights[0];
+  assert_eq!(desk.serial(), DESK);
+  assert_eq!(desk.address(), emulator.local_addr());
+  assert_eq!(desk.label().as_str(), "Desk");
+  assert_eq!(desk.capabilities().unwrap().name(), "LIFX A19");
+  assert!(!desk.is_on());
+  assert_eq!(lights[1].label().as_str(), "Shelf");
+}
+
+#[tokio::test]
+async fn should_toggle_and_set_color() {
+  let (emulator, mut lights) = lights().await;
+  let desk = &mut lights[0];
+
+  assert_eq!(desk.toggle(0).await.unwrap(), Power::On);
+  assert!(desk.is_on());
+  assert_eq!(emulator.bulb(DESK).await.unwrap().power, Power::On);
+
+  let color = Color::from_hsbk_f32(200.0, 0.75, 0.5, 4000);
+  desk.set_color(color, 0).await.unwrap();
+  assert_eq!(desk.color(), color);
+  assert_eq!(emulator.bulb(DESK).await.unwrap().color, color);
+
+  assert_eq!(desk.toggle(0).await.unwrap(), Power::Off);
+  assert_eq!(emulator.bulb(DESK).await.unwrap().power, Power::Off);
+  assert_eq!(emulator.bulb(SHELF).await.unwrap().power, Power::Off);
+}
+
+#[tokio::test]
+async fn should_refresh_state_changed_elsewhere() {
+  let (_emulator, mut lights) = lights().await;
+  let mut stale = lights[1].clone();
+  let shelf = &mut lights[1];
+
+  shelf.set_label("Bookshelf").await.unwrap();
+  shelf.set_power(Power::On, 0).await.unwrap();
+  assert_eq!(stale.label().as_str(), "Shelf");
+
+  let state = stale.refresh().await.unwrap();
+  assert_eq!(state.label.as_str(), "Bookshelf");
+  assert!(stale.is_on());
+}
@@ -1,6 +1,5 @@
 use lifx::*;
 use log::{info, trace};
-use std::convert::TryInto;
 use std::net::UdpSocket;
 use std::sync::Arc;
 use std::time::Duration;
@@ -16,38 +15,27 @@ async fn main() -> anyhow::Result<()> {
   let sender = Arc::clone(&client);
   tokio::spawn(async move {
     loop {
-      sender.get_state().await.unwrap();
+      match timeout(Duration::from_secs(1), sender.get_state(Target::All)).await {
+        Ok(state) => handle_state(state.unwrap()),
+        Err(_) => info!("did not receive state"),
+      }
       tokio::time::delay_for(Duration::from_secs(5)).await;
     }
   });
 
   loop {
-    let timer = timeout(Duration::from_secs(1), client.receive_message()).await;
-
-    if let Err(_) = timer {
-      info!("did not receive message");
-      continue;
-    }
-
-    let (addr, packet) = timer?.unwrap();
+    let (addr, packet) = client.receive_message().await?;
 
     trace!(
-      "addr {}, target {}, {:x?}",
+      "unsolicited type {} from addr {}, target {}, {:x?}",
+      packet.raw_message_type(),
       addr,
       packet.target(),
       packet.target().to_be_bytes()
     );
-
-    match packet.message_type() {
-      MessageType::State => handle_state(packet.try_into()?),
-      _ => continue,
-    }
   }
 }
 
 pub fn handle_state(payload: StatePayload) {
-  let label = std::str::from_utf8(&payload.label)
-    .unwrap()
-    .trim_end_matches(char::from(0));
-  info!("power: {}, label: {}", payload.power(), label)
+  info!("power: {}, label: {}", payload.power(), payload.label)
 }
@@ -1,7 +1,7 @@
 use lifx::*;
 use log::info;
-use std::convert::TryInto;
 use std::net::UdpSocket;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -10,23 +10,20 @@ async fn main() -> anyhow::Result<()> {
   let socket = UdpSocket::bind(&addr)?;
   let client = Client::new(1337, socket)?;
 
-  // client.set_power(Power::On, 11000).await?;
-  client.get_state().await?;
-
-  loop {
-    let (addr, packet) = client.receive_message().await?;
-    match packet.message_type() {
-      MessageType::State => handle_state(packet.try_into()?),
-      _ => continue,
-    }
+  let devices = discovery::discover(&client, Duration::from_secs(1)).await?;
+  for device in devices {
+    // client.set_power(device.target(), Power::On, 11000).await?;
+    let state = client.get_state(device.target()).await?;
+    info!("{} at {}:{}", device.target(), device.ip, device.port);
+    handle_state(state);
   }
+  Ok(())
 }
 
 pub fn handle_state(payload: StatePayload) {
-  let label = String::from_utf8(payload.label.to_vec()).unwrap();
   info!(
     "label: {}, power: {}, color: {}",
-    label,
+    payload.label,
     payload.power(),
     payload.color
   );
@@ -0,0 +1,95 @@
+#!/usr/bin/env python3
+"""Generates lifx/src/product/products.rs from LIFX's public products.json.
+
+    curl -sL https://raw.githubusercontent.com/LIFX/products/master/products.json \
+      | python3 lifx/scripts/products.py > lifx/src/product/products.rs
+    cargo fmt --all
+"""
+import json
+import sys
+
+FLAGS = [
+    "color",
+    "infrared",
+    "multizone",
+    "extended_multizone",
+    "matrix",
+    "chain",
+    "hev",
+    "relays",
+    "buttons",
+]
+
+
+def temperature_range(value):
+    if value is None:
+        return "None"
+    return "Some(({}, {}))".format(value[0], value[1])
+
+
+def features(values, defaults, indent):
+    fields = ["{}: {},".format(flag, str(bool(values[flag])).lower()) for flag in FLAGS if flag in values]
+    if "temperature_range" in values:
+        fields.append("temperature_range: {},".format(temperature_range(values["temperature_range"])))
+    pad = " " * indent
+    return "Features {{\n{}\n{}..{}\n{}}}".format(
+        "\n".join(pad + "  " + field for field in fields), pad + "  ", defaults, pad
+    )
+
+
+def upgrade(value):
+    extended = value["features"].get("extended_multizone")
+    return (
+        "      Upgrade {{\n"
+        "        major: {},\n"
+        "        minor: {},\n"
+        "        extended_multizone: {},\n"
+        "        temperature_range: {},\n"
+        "      }},"
+    ).format(
+        value["major"],
+        value["minor"],
+        "None" if extended is None else "Some({})".format(str(extended).lower()),
+        temperature_range(value["features"]["temperature_range"])
+        if "temperature_range" in value["features"]
+        else "None",
+    )
+
+
+def main():
+    vendors = json.load(sys.stdin)
+    print("// Generated by lifx/scripts/products.py from LIFX's products.json, don't edit by hand.")
+    print("use super::{Features, Product, Upgrade};")
+    print()
+    for vendor in vendors:
+        defaults = vendor["defaults"]
+        print("/// Features of {} products unless listed otherwise.".format(vendor["name"]))
+        print("const VENDOR_{}: Features = Features {{".format(vendor["vid"]))
+        for flag in FLAGS:
+            print("  {}: {},".format(flag, str(bool(defaults.get(flag, False))).lower()))
+        print("  temperature_range: {},".format(temperature_range(defaults.get("temperature_range"))))
+        print("};")
+        print()
+    print("pub(super) const PRODUCTS: &[Product] = &[")
+    for vendor in vendors:
+        for product in vendor["products"]:
+            upgrades = product.get("upgrades", [])
+            print("  Product {")
+            print("    vendor: {},".format(vendor["vid"]))
+            print("    product: {},".format(product["pid"]))
+            print("    name: {},".format(json.dumps(product["name"])))
+            defaults = "VENDOR_{}".format(vendor["vid"])
+            print("    features: {},".format(features(product["features"], defaults, 4)))
+            if upgrades:
+                print("    upgrades: &[")
+                for value in upgrades:
+                    print(upgrade(value))
+                print("    ],")
+            else:
+                print("    upgrades: &[],")
+            print("  },")
+    print("];")
+
+
+if __name__ == "__main__":
+    main()
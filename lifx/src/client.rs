@@ -1,18 +1,43 @@
+use crate::config::ClientConfig;
+use crate::dispatcher::{Addresses, Dispatcher, InFlight, Response};
+use crate::error::{TimeoutError, UnsupportedError};
 use crate::message::*;
-use crate::proto::{IncomingPacket, MessageType, OutgoingPacket, Power};
+use crate::product::{Capabilities, Features};
+use crate::proto::{
+  Deserializable, IncomingPacket, MessageType, OutgoingPacket, Power, Serializable,
+};
 use crate::reader::Reader;
+use crate::retry::RetryPolicy;
+use crate::target::Target;
 use crate::writer::Writer;
-use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use log::warn;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::UdpSocket;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+use tokio::time::{timeout_at, Instant};
 
 const EMPTY_PAYLOAD: EmptyPayload = EmptyPayload {};
+const UNSOLICITED_CAPACITY: usize = 64;
+/// How long a command waits on a device to say what it supports before sending anyway.
+const LOOKUP_TIMEOUT: Duration = Duration::from_millis(500);
 
 pub struct Client {
   id: u32,
-  reader: Arc<Mutex<Reader>>,
+  sequence: AtomicU8,
+  effect_id: AtomicU32,
+  retry_policy: RetryPolicy,
+  config: ClientConfig,
   writer: Arc<Mutex<Writer>>,
+  in_flight: InFlight,
+  addresses: Addresses,
+  capabilities: Mutex<HashMap<Target, Option<Capabilities>>>,
+  unsolicited: broadcast::Sender<Response>,
+  receiver: Mutex<broadcast::Receiver<Response>>,
+  _shutdown: oneshot::Sender<()>,
 }
 
 impl Client {
@@ -24,92 +49,1247 @@ impl Client {
     let reader = Reader::new(recv_half);
     let writer = Writer::new(send_half);
 
+    let in_flight = InFlight::default();
+    let addresses = Addresses::default();
+    let (unsolicited, receiver) = broadcast::channel(UNSOLICITED_CAPACITY);
+    let (shutdown, shutdown_rx) = oneshot::channel();
+
+    let dispatcher = Dispatcher::new(
+      id,
+      reader,
+      in_flight.clone(),
+      addresses.clone(),
+      unsolicited.clone(),
+    );
+    tokio::spawn(dispatcher.run(shutdown_rx));
+
     Ok(Self {
       id,
-      reader: Arc::new(Mutex::new(reader)),
+      sequence: AtomicU8::new(0),
+      effect_id: AtomicU32::new(first_effect_id()),
+      retry_policy: RetryPolicy::default(),
+      config: ClientConfig::default(),
       writer: Arc::new(Mutex::new(writer)),
+      in_flight,
+      addresses,
+      capabilities: Mutex::new(HashMap::new()),
+      unsolicited,
+      receiver: Mutex::new(receiver),
+      _shutdown: shutdown,
     })
   }
 
-  pub async fn get_service(&self) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(
-      0,
-      self.id,
-      false,
-      true,
-      MessageType::GetService,
-      EMPTY_PAYLOAD,
-    )?;
-    self.send_packet(packet).await
+  /// Replaces the policy used to resend requests that go unanswered.
+  pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+    self.retry_policy = retry_policy;
+    self
   }
 
-  pub async fn get_host_info(&self) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(
-      0,
-      self.id,
-      false,
-      true,
-      MessageType::GetHostInfo,
-      EMPTY_PAYLOAD,
-    )?;
-    self.send_packet(packet).await
+  pub fn retry_policy(&self) -> &RetryPolicy {
+    &self.retry_policy
   }
 
-  pub async fn get_host_firmware(&self) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(
-      0,
-      self.id,
-      false,
-      true,
-      MessageType::GetHostFirmware,
-      EMPTY_PAYLOAD,
-    )?;
-    self.send_packet(packet).await
+  /// Replaces where packets go before a device's own address has been learned.
+  pub fn with_config(mut self, config: ClientConfig) -> Self {
+    self.config = config;
+    self
   }
 
-  pub async fn get_wifi_info(&self) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(
-      0,
-      self.id,
-      false,
-      true,
-      MessageType::GetWifiFirmware,
-      EMPTY_PAYLOAD,
-    )?;
-    self.send_packet(packet).await
+  pub fn config(&self) -> &ClientConfig {
+    &self.config
   }
 
-  pub async fn set_power(&self, level: Power, duration: u32) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(
-      0,
-      self.id,
-      true,
-      false,
-      MessageType::SetPower,
-      SetPowerPayload { level, duration },
-    )?;
-    self.send_packet(packet).await
+  pub async fn get_service(&self, target: Target) -> anyhow::Result<StateServicePayload> {
+    self
+      .request(
+        target,
+        MessageType::GetService,
+        EMPTY_PAYLOAD,
+        MessageType::StateService,
+      )
+      .await
+  }
+
+  pub async fn get_host_info(&self, target: Target) -> anyhow::Result<StateHostInfoPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetHostInfo,
+        EMPTY_PAYLOAD,
+        MessageType::StateHostInfo,
+      )
+      .await
+  }
+
+  pub async fn get_host_firmware(&self, target: Target) -> anyhow::Result<FirmwarePayload> {
+    self
+      .request(
+        target,
+        MessageType::GetHostFirmware,
+        EMPTY_PAYLOAD,
+        MessageType::StateHostFirmware,
+      )
+      .await
+  }
+
+  pub async fn get_version(&self, target: Target) -> anyhow::Result<StateVersionPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetVersion,
+        EMPTY_PAYLOAD,
+        MessageType::StateVersion,
+      )
+      .await
+  }
+
+  /// What a device can do, looked up from its product and firmware the first time it's asked.
+  ///
+  /// `None` for `Target::All`, for products missing from the registry and for
+  /// devices whose lookup already failed ahead of an earlier command.
+  pub async fn capabilities(&self, target: Target) -> anyhow::Result<Option<Capabilities>> {
+    self.look_up(target, &self.retry_policy).await
+  }
+
+  /// Asks for the product and firmware at the same time, resending on `retry_policy`.
+  async fn look_up(
+    &self,
+    target: Target,
+    retry_policy: &RetryPolicy,
+  ) -> anyhow::Result<Option<Capabilities>> {
+    if target == Target::All {
+      return Ok(None);
+    }
+    if let Some(capabilities) = self.capabilities.lock().await.get(&target) {
+      return Ok(*capabilities);
+    }
+    let (version, firmware) = tokio::join!(
+      self.exchange(
+        target,
+        MessageType::GetVersion,
+        EMPTY_PAYLOAD,
+        MessageType::StateVersion,
+        retry_policy,
+      ),
+      self.exchange(
+        target,
+        MessageType::GetHostFirmware,
+        EMPTY_PAYLOAD,
+        MessageType::StateHostFirmware,
+        retry_policy,
+      )
+    );
+    let version = StateVersionPayload::deserialize(&mut version?.payload())?;
+    let firmware = FirmwarePayload::deserialize(&mut firmware?.payload())?;
+    let capabilities = Capabilities::new(&version, &firmware);
+    self.capabilities.lock().await.insert(target, capabilities);
+    Ok(capabilities)
+  }
+
+  pub async fn get_wifi_info(&self, target: Target) -> anyhow::Result<StateWifiInfoPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetWifiInfo,
+        EMPTY_PAYLOAD,
+        MessageType::StateWifiInfo,
+      )
+      .await
+  }
+
+  pub async fn get_wifi_firmware(&self, target: Target) -> anyhow::Result<FirmwarePayload> {
+    self
+      .request(
+        target,
+        MessageType::GetWifiFirmware,
+        EMPTY_PAYLOAD,
+        MessageType::StateWifiFirmware,
+      )
+      .await
+  }
+
+  pub async fn get_label(&self, target: Target) -> anyhow::Result<LabelPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetLabel,
+        EMPTY_PAYLOAD,
+        MessageType::StateLabel,
+      )
+      .await
+  }
+
+  /// Renames the device, truncating names longer than 32 bytes.
+  pub async fn set_label(&self, target: Target, label: impl Into<Label>) -> anyhow::Result<()> {
+    let payload = LabelPayload::new(label.into());
+    self
+      .send_acknowledged(target, MessageType::SetLabel, payload, &self.retry_policy)
+      .await
+  }
+
+  pub async fn get_location(&self, target: Target) -> anyhow::Result<LocationPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetLocation,
+        EMPTY_PAYLOAD,
+        MessageType::StateLocation,
+      )
+      .await
+  }
+
+  /// Moves the device into `location`, naming the location `label`.
+  pub async fn set_location(
+    &self,
+    target: Target,
+    location: impl Into<LifxUuid>,
+    label: impl Into<Label>,
+  ) -> anyhow::Result<()> {
+    let payload = LocationPayload::new(location.into(), label.into());
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetLocation,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn get_group(&self, target: Target) -> anyhow::Result<GroupPayload> {
+    self
+      .request(
+        target,
+        MessageType::GetGroup,
+        EMPTY_PAYLOAD,
+        MessageType::StateGroup,
+      )
+      .await
   }
 
-  pub async fn set_color(&self, color: Color, duration: u32) -> anyhow::Result<()> {
+  /// Moves the device into `group`, naming the group `label`.
+  pub async fn set_group(
+    &self,
+    target: Target,
+    group: impl Into<LifxUuid>,
+    label: impl Into<Label>,
+  ) -> anyhow::Result<()> {
+    let payload = GroupPayload::new(group.into(), label.into());
+    self
+      .send_acknowledged(target, MessageType::SetGroup, payload, &self.retry_policy)
+      .await
+  }
+
+  pub async fn set_power(&self, target: Target, level: Power, duration: u32) -> anyhow::Result<()> {
+    let payload = SetPowerPayload { level, duration };
+    self
+      .send_acknowledged(target, MessageType::SetPower, payload, &self.retry_policy)
+      .await
+  }
+
+  pub async fn set_color(&self, target: Target, color: Color, duration: u32) -> anyhow::Result<()> {
+    let capabilities = self
+      .require(target, "light", |features| {
+        features.temperature_range.is_some()
+      })
+      .await?;
+    let color = capabilities.map_or(color, |capabilities| capabilities.restrict(color));
     let payload = SetColorPayload { color, duration };
-    let packet = OutgoingPacket::new(0, self.id, true, false, MessageType::SetColor, payload)?;
-    self.send_packet(packet).await
+    self
+      .send_acknowledged(target, MessageType::SetColor, payload, &self.retry_policy)
+      .await
+  }
+
+  /// Runs a waveform effect, such as a breathe or pulse alert.
+  pub async fn set_waveform(
+    &self,
+    target: Target,
+    mut payload: SetWaveformPayload,
+  ) -> anyhow::Result<()> {
+    let capabilities = self
+      .require(target, "light", |features| {
+        features.temperature_range.is_some()
+      })
+      .await?;
+    if let Some(capabilities) = capabilities {
+      payload.color = capabilities.restrict(payload.color);
+    }
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetWaveform,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  /// Runs a waveform effect on only the channels flagged in the payload.
+  pub async fn set_waveform_optional(
+    &self,
+    target: Target,
+    mut payload: SetWaveformOptionalPayload,
+  ) -> anyhow::Result<()> {
+    let capabilities = self
+      .require(target, "light", |features| {
+        features.temperature_range.is_some()
+      })
+      .await?;
+    if let Some(capabilities) = capabilities {
+      payload.color = capabilities.restrict(payload.color);
+    }
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetWaveformOptional,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn get_state(&self, target: Target) -> anyhow::Result<StatePayload> {
+    self
+      .request(target, MessageType::Get, EMPTY_PAYLOAD, MessageType::State)
+      .await
+  }
+
+  /// Reads the infrared brightness of a LIFX+ bulb as a percentage.
+  pub async fn get_infrared(&self, target: Target) -> anyhow::Result<u16> {
+    self
+      .require(target, "infrared", |features| features.infrared)
+      .await?;
+    let payload: InfraredPayload = self
+      .request(
+        target,
+        MessageType::GetInfrared,
+        EMPTY_PAYLOAD,
+        MessageType::StateInfrared,
+      )
+      .await?;
+    Ok(payload.brightness())
+  }
+
+  /// Sets the infrared brightness of a LIFX+ bulb, as a percentage.
+  pub async fn set_infrared(&self, target: Target, brightness: u16) -> anyhow::Result<()> {
+    self
+      .require(target, "infrared", |features| features.infrared)
+      .await?;
+    let payload = InfraredPayload::new(brightness);
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetInfrared,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn get_hev_cycle(&self, target: Target) -> anyhow::Result<StateHevCyclePayload> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    self
+      .request(
+        target,
+        MessageType::GetHevCycle,
+        EMPTY_PAYLOAD,
+        MessageType::StateHevCycle,
+      )
+      .await
+  }
+
+  /// Starts a HEV cycle on a LIFX Clean bulb, zero seconds using the configured duration.
+  pub async fn start_hev_cycle(&self, target: Target, duration_s: u32) -> anyhow::Result<()> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    let payload = SetHevCyclePayload {
+      enable: true,
+      duration_s,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetHevCycle,
+        payload,
+        &self.retry_policy,
+      )
+      .await
   }
 
-  pub async fn get_state(&self) -> anyhow::Result<()> {
-    let packet = OutgoingPacket::new(0, self.id, false, true, MessageType::Get, EMPTY_PAYLOAD)?;
-    self.send_packet(packet).await
+  pub async fn stop_hev_cycle(&self, target: Target) -> anyhow::Result<()> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    let payload = SetHevCyclePayload {
+      enable: false,
+      duration_s: 0,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetHevCycle,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn get_hev_cycle_configuration(
+    &self,
+    target: Target,
+  ) -> anyhow::Result<HevCycleConfigurationPayload> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    self
+      .request(
+        target,
+        MessageType::GetHevCycleConfiguration,
+        EMPTY_PAYLOAD,
+        MessageType::StateHevCycleConfiguration,
+      )
+      .await
+  }
+
+  pub async fn set_hev_cycle_configuration(
+    &self,
+    target: Target,
+    indication: bool,
+    duration_s: u32,
+  ) -> anyhow::Result<()> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    let payload = HevCycleConfigurationPayload {
+      indication,
+      duration_s,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetHevCycleConfiguration,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn get_last_hev_cycle_result(
+    &self,
+    target: Target,
+  ) -> anyhow::Result<LastHevCycleResult> {
+    self.require(target, "HEV", |features| features.hev).await?;
+    let payload: StateLastHevCycleResultPayload = self
+      .request(
+        target,
+        MessageType::GetLastHevCycleResult,
+        EMPTY_PAYLOAD,
+        MessageType::StateLastHevCycleResult,
+      )
+      .await?;
+    Ok(payload.result)
+  }
+
+  /// Reads whether a relay on a LIFX Switch is on or off.
+  pub async fn get_relay_power(&self, target: Target, relay_index: u8) -> anyhow::Result<Power> {
+    self
+      .require(target, "relays", |features| features.relays)
+      .await?;
+    let payload = GetRPowerPayload { relay_index };
+    let state: StateRPowerPayload = self
+      .request(
+        target,
+        MessageType::GetRPower,
+        payload,
+        MessageType::StateRPower,
+      )
+      .await?;
+    Ok(state.level)
+  }
+
+  pub async fn set_relay_power(
+    &self,
+    target: Target,
+    relay_index: u8,
+    level: Power,
+  ) -> anyhow::Result<()> {
+    self
+      .require(target, "relays", |features| features.relays)
+      .await?;
+    let payload = SetRPowerPayload { relay_index, level };
+    self
+      .send_acknowledged(target, MessageType::SetRPower, payload, &self.retry_policy)
+      .await
+  }
+
+  /// Sets a range of zones on a multizone device to a single colour.
+  pub async fn set_color_zones(
+    &self,
+    target: Target,
+    start_index: u8,
+    end_index: u8,
+    color: Color,
+    duration: u32,
+    apply: ApplicationRequest,
+  ) -> anyhow::Result<()> {
+    let capabilities = self
+      .require(target, "multizone", |features| features.multizone)
+      .await?;
+    let color = capabilities.map_or(color, |capabilities| capabilities.restrict(color));
+    let payload = SetColorZonesPayload {
+      start_index,
+      end_index,
+      color,
+      duration,
+      apply,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetColorZones,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  /// Reads every zone of a multizone device, in order, falling back to the
+  /// older zone messages on firmware without extended multizone support.
+  ///
+  /// Fails for `Target::All`, as replies from several devices can't be told apart.
+  pub async fn get_zones(&self, target: Target) -> anyhow::Result<Vec<Color>> {
+    if target == Target::All {
+      return Err(anyhow::Error::msg(
+        "Zones can only be read from a single device",
+      ));
+    }
+    let capabilities = self
+      .require(target, "multizone", |features| features.multizone)
+      .await?;
+    let extended = capabilities.is_none_or(|capabilities| capabilities.features.extended_multizone);
+    let (sequence, mut responses) = self
+      .in_flight
+      .register(&self.sequence, target.into())
+      .await?;
+    let packet = if extended {
+      self.packet(
+        sequence,
+        target.into(),
+        MessageType::GetExtendedColorZones,
+        EMPTY_PAYLOAD,
+        false,
+      )
+    } else {
+      let payload = GetColorZonesPayload {
+        start_index: 0,
+        end_index: 255,
+      };
+      self.packet(
+        sequence,
+        target.into(),
+        MessageType::GetColorZones,
+        payload,
+        false,
+      )
+    };
+    let packet = match packet {
+      Ok(packet) => packet,
+      Err(err) => {
+        self.in_flight.remove(sequence).await;
+        return Err(err);
+      }
+    };
+
+    let result = self.read_zones(packet, &mut responses, extended).await;
+    self.in_flight.remove(sequence).await;
+    result
+  }
+
+  /// Writes the whole zone buffer of a multizone device, applying it once every zone is sent.
+  pub async fn set_zones(
+    &self,
+    target: Target,
+    zones: &[Color],
+    duration: u32,
+  ) -> anyhow::Result<()> {
+    let capabilities = self
+      .require(target, "multizone", |features| features.multizone)
+      .await?;
+    let zones = zones
+      .iter()
+      .map(|color| capabilities.map_or(*color, |capabilities| capabilities.restrict(*color)))
+      .collect::<Vec<_>>();
+    if !capabilities.is_none_or(|capabilities| capabilities.features.extended_multizone) {
+      return self.set_zone_ranges(target, &zones, duration).await;
+    }
+
+    let chunks = zones.chunks(EXTENDED_ZONES).count();
+    for (index, colors) in zones.chunks(EXTENDED_ZONES).enumerate() {
+      let apply = if index + 1 == chunks {
+        ApplicationRequest::Apply
+      } else {
+        ApplicationRequest::NoApply
+      };
+      let payload = SetExtendedColorZonesPayload {
+        duration,
+        apply,
+        zone_index: (index * EXTENDED_ZONES) as u16,
+        colors: colors.to_vec(),
+      };
+      self
+        .send_acknowledged(
+          target,
+          MessageType::SetExtendedColorZones,
+          payload,
+          &self.retry_policy,
+        )
+        .await?;
+    }
+    Ok(())
   }
 
+  /// Older firmware takes a single range of zones per message, so runs of one
+  /// colour share a message. Every message is acknowledged before the next is
+  /// sent, so the final one only applies once the whole strip is buffered.
+  async fn set_zone_ranges(
+    &self,
+    target: Target,
+    zones: &[Color],
+    duration: u32,
+  ) -> anyhow::Result<()> {
+    if zones.len() > u8::MAX as usize + 1 {
+      return Err(anyhow::Error::msg(format!(
+        "{} zones can't be addressed without extended multizone support",
+        zones.len()
+      )));
+    }
+    let mut runs: Vec<(usize, usize, Color)> = vec![];
+    for (index, color) in zones.iter().enumerate() {
+      match runs.last_mut() {
+        Some((_, end, run)) if run == color => *end = index,
+        _ => runs.push((index, index, *color)),
+      }
+    }
+
+    let count = runs.len();
+    for (index, (start, end, color)) in runs.into_iter().enumerate() {
+      let payload = SetColorZonesPayload {
+        start_index: start as u8,
+        end_index: end as u8,
+        color,
+        duration,
+        apply: if index + 1 == count {
+          ApplicationRequest::Apply
+        } else {
+          ApplicationRequest::NoApply
+        },
+      };
+      self
+        .send_acknowledged(
+          target,
+          MessageType::SetColorZones,
+          payload,
+          &self.retry_policy,
+        )
+        .await?;
+    }
+    Ok(())
+  }
+
+  pub async fn get_device_chain(&self, target: Target) -> anyhow::Result<StateDeviceChainPayload> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    self
+      .request(
+        target,
+        MessageType::GetDeviceChain,
+        EMPTY_PAYLOAD,
+        MessageType::StateDeviceChain,
+      )
+      .await
+  }
+
+  /// Records where a tile sits relative to the others in its chain.
+  pub async fn set_user_position(
+    &self,
+    target: Target,
+    tile_index: u8,
+    user_x: f32,
+    user_y: f32,
+  ) -> anyhow::Result<()> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    let payload = SetUserPositionPayload {
+      tile_index,
+      user_x,
+      user_y,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetUserPosition,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  /// Reads the colours of one tile as rows of `width` pixels, a band of rows per request.
+  pub async fn get_tile_frame(
+    &self,
+    target: Target,
+    tile_index: u8,
+    width: u8,
+    height: u8,
+  ) -> anyhow::Result<Vec<Vec<Color>>> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    let mut frame = vec![];
+    for (y, rows) in tile_bands(width as usize, height as usize)? {
+      let payload = Get64Payload {
+        tile_index,
+        length: 1,
+        x: 0,
+        y,
+        width,
+      };
+      let state: State64Payload = self
+        .request(target, MessageType::Get64, payload, MessageType::State64)
+        .await?;
+      frame.extend(
+        state
+          .colors
+          .chunks(width as usize)
+          .take(rows)
+          .map(|row| row.to_vec()),
+      );
+    }
+    Ok(frame)
+  }
+
+  /// Pushes a frame of rows to one tile without waiting for an acknowledgement,
+  /// so frames can be streamed as fast as the device accepts them.
+  ///
+  /// Frames of more than `TILE_COLORS` pixels are sent as several bands of rows.
+  pub async fn set_tile_frame(
+    &self,
+    target: Target,
+    tile_index: u8,
+    frame: &[Vec<Color>],
+    duration: u32,
+  ) -> anyhow::Result<()> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    let width = frame.first().map_or(0, Vec::len);
+    if frame.iter().any(|row| row.len() != width) {
+      return Err(anyhow::Error::msg(
+        "Every row of a frame must be the same width",
+      ));
+    }
+    for (y, rows) in tile_bands(width, frame.len())? {
+      let start = y as usize;
+      let payload = Set64Payload {
+        tile_index,
+        length: 1,
+        x: 0,
+        y,
+        width: width as u8,
+        duration,
+        colors: frame[start..start + rows].concat(),
+      };
+      self
+        .send_unacknowledged(target, MessageType::Set64, payload)
+        .await?;
+    }
+    Ok(())
+  }
+
+  /// Starts a firmware effect across the zones of a multizone device.
+  pub async fn start_multizone_effect(
+    &self,
+    target: Target,
+    effect: MultiZoneEffect,
+    speed: u32,
+    duration: u64,
+  ) -> anyhow::Result<()> {
+    self
+      .require(target, "multizone", |features| features.multizone)
+      .await?;
+    let payload = MultiZoneEffectPayload {
+      instance_id: self.next_effect_id(),
+      effect,
+      speed,
+      duration,
+    };
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetMultiZoneEffect,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn stop_multizone_effect(&self, target: Target) -> anyhow::Result<()> {
+    self
+      .start_multizone_effect(target, MultiZoneEffect::Off, 0, 0)
+      .await
+  }
+
+  pub async fn get_multizone_effect(
+    &self,
+    target: Target,
+  ) -> anyhow::Result<MultiZoneEffectPayload> {
+    self
+      .require(target, "multizone", |features| features.multizone)
+      .await?;
+    self
+      .request(
+        target,
+        MessageType::GetMultiZoneEffect,
+        EMPTY_PAYLOAD,
+        MessageType::StateMultiZoneEffect,
+      )
+      .await
+  }
+
+  /// Starts a firmware effect on a matrix device, drawing from `palette` where the effect uses one.
+  pub async fn start_tile_effect(
+    &self,
+    target: Target,
+    effect: TileEffect,
+    speed: u32,
+    duration: u64,
+    palette: &[Color],
+  ) -> anyhow::Result<()> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    let payload = SetTileEffectPayload(TileEffectPayload {
+      instance_id: self.next_effect_id(),
+      effect,
+      speed,
+      duration,
+      palette: palette.to_vec(),
+    });
+    self
+      .send_acknowledged(
+        target,
+        MessageType::SetTileEffect,
+        payload,
+        &self.retry_policy,
+      )
+      .await
+  }
+
+  pub async fn stop_tile_effect(&self, target: Target) -> anyhow::Result<()> {
+    self
+      .start_tile_effect(target, TileEffect::Off, 0, 0, &[])
+      .await
+  }
+
+  pub async fn get_tile_effect(&self, target: Target) -> anyhow::Result<TileEffectPayload> {
+    self
+      .require(target, "matrix", |features| features.matrix)
+      .await?;
+    let StateTileEffectPayload(payload) = self
+      .request(
+        target,
+        MessageType::GetTileEffect,
+        GetTileEffectPayload {},
+        MessageType::StateTileEffect,
+      )
+      .await?;
+    Ok(payload)
+  }
+
+  /// Sends the packet straight to its target when the device's address is known,
+  /// otherwise to every broadcast address and peer in the config.
   pub async fn send_packet(&self, packet: OutgoingPacket) -> anyhow::Result<()> {
+    let addrs = match self.addresses.get(packet.target()).await {
+      Some(addr) => vec![addr],
+      None => self.config.destinations(),
+    };
+    if addrs.is_empty() {
+      return Err(anyhow::Error::msg(
+        "No broadcast addresses or peers are configured",
+      ));
+    }
     let mut writer = self.writer.lock().await;
-    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(255, 255, 255, 255)), 56700);
-    writer.write_packet(&addr, packet).await
+    for addr in addrs {
+      writer.write_packet(&addr, packet.clone()).await?;
+    }
+    Ok(())
   }
 
+  /// The address a device was last heard from, if it has replied to this client.
+  pub async fn address(&self, target: Target) -> Option<SocketAddr> {
+    self.addresses.get(target.into()).await
+  }
+
+  /// Waits for the next packet that isn't a reply to one of this client's requests.
   pub async fn receive_message(&self) -> anyhow::Result<(SocketAddr, IncomingPacket)> {
-    let mut reader = self.reader.lock().await;
-    reader.read_packet().await
+    let mut receiver = self.receiver.lock().await;
+    loop {
+      match receiver.recv().await {
+        Ok(response) => return Ok(response),
+        Err(broadcast::RecvError::Lagged(_)) => continue,
+        Err(broadcast::RecvError::Closed) => {
+          return Err(anyhow::Error::msg("Dispatcher has stopped"))
+        }
+      }
+    }
+  }
+
+  /// Subscribes to packets that aren't a reply to one of this client's requests.
+  pub fn subscribe(&self) -> broadcast::Receiver<(SocketAddr, IncomingPacket)> {
+    self.unsolicited.subscribe()
+  }
+
+  /// Sends a message, resending it according to `retry_policy` until the device acknowledges it.
+  pub async fn send_acknowledged(
+    &self,
+    target: Target,
+    message_type: MessageType,
+    payload: impl Serializable,
+    retry_policy: &RetryPolicy,
+  ) -> anyhow::Result<()> {
+    let response = MessageType::Acknowlegement;
+    self
+      .exchange(target, message_type, payload, response, retry_policy)
+      .await?;
+    Ok(())
+  }
+
+  /// Sends a message once, asking for neither an acknowledgement nor a reply.
+  ///
+  /// The sequence is held in flight while sending, so a late reply to another
+  /// request can't share it.
+  async fn send_unacknowledged(
+    &self,
+    target: Target,
+    message_type: MessageType,
+    payload: impl Serializable,
+  ) -> anyhow::Result<()> {
+    let target = target.into();
+    let (sequence, _responses) = self.in_flight.register(&self.sequence, target).await?;
+    let result = match OutgoingPacket::new(
+      sequence,
+      self.id,
+      target,
+      false,
+      false,
+      message_type,
+      payload,
+    ) {
+      Ok(packet) => self.send_packet(packet).await,
+      Err(err) => Err(err),
+    };
+    self.in_flight.remove(sequence).await;
+    result
+  }
+
+  /// Sends a request and resolves with the first `response` carrying the same sequence.
+  async fn request<T: Deserializable>(
+    &self,
+    target: Target,
+    message_type: MessageType,
+    payload: impl Serializable,
+    response: MessageType,
+  ) -> anyhow::Result<T> {
+    let packet = self
+      .exchange(target, message_type, payload, response, &self.retry_policy)
+      .await?;
+    T::deserialize(&mut packet.payload())
   }
+
+  async fn exchange(
+    &self,
+    target: Target,
+    message_type: MessageType,
+    payload: impl Serializable,
+    response: MessageType,
+    retry_policy: &RetryPolicy,
+  ) -> anyhow::Result<IncomingPacket> {
+    let target = target.into();
+    // an acknowledgement is all we need unless the request asks for state
+    let ack_required = response == MessageType::Acknowlegement;
+    let (sequence, mut responses) = self.in_flight.register(&self.sequence, target).await?;
+    let result = match self.packet(sequence, target, message_type, payload, ack_required) {
+      Ok(packet) => {
+        self
+          .resend(packet, &mut responses, response, retry_policy)
+          .await
+      }
+      Err(err) => Err(err),
+    };
+    self.in_flight.remove(sequence).await;
+    result
+  }
+
+  /// Broadcasts a request and gathers every `response` that arrives within `window`,
+  /// resending it on the retry policy's schedule in case the broadcast was dropped.
+  pub(crate) async fn collect(
+    &self,
+    message_type: MessageType,
+    payload: impl Serializable,
+    response: MessageType,
+    window: Duration,
+  ) -> anyhow::Result<Vec<Response>> {
+    let (sequence, mut responses) = self.in_flight.register(&self.sequence, 0).await?;
+    let packet = match OutgoingPacket::new(sequence, self.id, 0, false, true, message_type, payload)
+    {
+      Ok(packet) => packet,
+      Err(err) => {
+        self.in_flight.remove(sequence).await;
+        return Err(err);
+      }
+    };
+    let deadline = Instant::now() + window;
+
+    let mut collected = vec![];
+    let mut attempts = 0;
+    let result = loop {
+      let now = Instant::now();
+      if now >= deadline {
+        break Ok(collected);
+      }
+      if attempts < self.retry_policy.attempts {
+        if let Err(err) = self.send_packet(packet.clone()).await {
+          break Err(err);
+        }
+      }
+      let wait = (now + self.retry_policy.wait(attempts)).min(deadline);
+      attempts += 1;
+
+      match Self::gather(&mut responses, response, wait, &mut collected).await {
+        Ok(()) => continue,
+        Err(err) => break Err(err),
+      }
+    };
+    self.in_flight.remove(sequence).await;
+    result
+  }
+
+  async fn gather(
+    responses: &mut mpsc::UnboundedReceiver<Response>,
+    response: MessageType,
+    until: Instant,
+    collected: &mut Vec<Response>,
+  ) -> anyhow::Result<()> {
+    loop {
+      match timeout_at(until, responses.recv()).await {
+        Ok(Some(received)) if received.1.message_type() == Ok(response) => collected.push(received),
+        Ok(Some(_)) => continue,
+        Ok(None) => return Err(anyhow::Error::msg("Dispatcher has stopped")),
+        Err(_) => return Ok(()),
+      }
+    }
+  }
+
+  /// Devices with more zones than fit in one message reply several times to a single request.
+  async fn read_zones(
+    &self,
+    packet: OutgoingPacket,
+    responses: &mut mpsc::UnboundedReceiver<Response>,
+    extended: bool,
+  ) -> anyhow::Result<Vec<Color>> {
+    let response = if extended {
+      MessageType::StateExtendedColorZones
+    } else {
+      MessageType::StateMultiZone
+    };
+    let message_type = packet.message_type();
+    let started = Instant::now();
+    let first = self
+      .resend(packet, responses, response, &self.retry_policy)
+      .await?;
+    let (zones_count, mut zone_index, mut colors) = zone_chunk(&first, extended)?;
+
+    let mut zones = vec![None; zones_count];
+    loop {
+      for (zone, color) in zones.iter_mut().skip(zone_index).zip(colors) {
+        *zone = Some(color);
+      }
+      if zones.iter().all(Option::is_some) {
+        return Ok(zones.into_iter().flatten().collect());
+      }
+
+      let deadline = started + self.retry_policy.deadline;
+      let next = match timeout_at(deadline, Self::wait_for(responses, response)).await {
+        Ok(next) => next?,
+        Err(_) => {
+          return Err(
+            TimeoutError {
+              message_type,
+              attempts: 1,
+              elapsed: started.elapsed(),
+            }
+            .into(),
+          )
+        }
+      };
+      let (_, index, chunk) = zone_chunk(&next, extended)?;
+      zone_index = index;
+      colors = chunk;
+    }
+  }
+
+  async fn resend(
+    &self,
+    packet: OutgoingPacket,
+    responses: &mut mpsc::UnboundedReceiver<Response>,
+    response: MessageType,
+    retry_policy: &RetryPolicy,
+  ) -> anyhow::Result<IncomingPacket> {
+    let started = Instant::now();
+    let deadline = started + retry_policy.deadline;
+    let message_type = packet.message_type();
+
+    let mut attempts = 0;
+    while attempts < retry_policy.attempts && Instant::now() < deadline {
+      self.send_packet(packet.clone()).await?;
+      let wait = (Instant::now() + retry_policy.wait(attempts)).min(deadline);
+      attempts += 1;
+
+      if let Ok(result) = timeout_at(wait, Self::wait_for(responses, response)).await {
+        return result;
+      }
+    }
+
+    Err(
+      TimeoutError {
+        message_type,
+        attempts,
+        elapsed: started.elapsed(),
+      }
+      .into(),
+    )
+  }
+
+  async fn wait_for(
+    responses: &mut mpsc::UnboundedReceiver<Response>,
+    response: MessageType,
+  ) -> anyhow::Result<IncomingPacket> {
+    loop {
+      match responses.recv().await {
+        Some((_, packet)) if packet.message_type() == Ok(response) => return Ok(packet),
+        Some(_) => continue,
+        None => return Err(anyhow::Error::msg("Dispatcher has stopped")),
+      }
+    }
+  }
+
+  fn packet(
+    &self,
+    sequence: u8,
+    target: u64,
+    message_type: MessageType,
+    payload: impl Serializable,
+    ack_required: bool,
+  ) -> anyhow::Result<OutgoingPacket> {
+    OutgoingPacket::new(
+      sequence,
+      self.id,
+      target,
+      ack_required,
+      !ack_required,
+      message_type,
+      payload,
+    )
+  }
+
+  /// Devices tell effects apart by instance id, so each one started gets a fresh id.
+  fn next_effect_id(&self) -> u32 {
+    self.effect_id.fetch_add(1, Ordering::Relaxed)
+  }
+
+  /// Fails with `UnsupportedError` when the device is known to lack `feature`.
+  ///
+  /// The lookup gets a single short attempt, and a failed one is remembered as
+  /// unknown capabilities, so an unreachable device doesn't hold up every command.
+  async fn require(
+    &self,
+    target: Target,
+    feature: &'static str,
+    supported: fn(&Features) -> bool,
+  ) -> anyhow::Result<Option<Capabilities>> {
+    let timeout = LOOKUP_TIMEOUT.min(self.retry_policy.deadline);
+    let lookup_policy = RetryPolicy::new(1, timeout, timeout);
+    let capabilities = match self.look_up(target, &lookup_policy).await {
+      Ok(capabilities) => capabilities,
+      Err(err) => {
+        // carry on as for an unknown product rather than fail over a message the caller never sent
+        warn!("unable to look up what {} supports: {}", target, err);
+        self.capabilities.lock().await.insert(target, None);
+        None
+      }
+    };
+    match capabilities {
+      Some(capabilities) if !supported(&capabilities.features) => Err(
+        UnsupportedError {
+          target,
+          product: capabilities.name(),
+          feature,
+        }
+        .into(),
+      ),
+      _ => Ok(capabilities),
+    }
+  }
+}
+
+/// Splits a tile `width` pixels wide into bands of whole rows that each fit
+/// in one `Set64` or `State64`, as the first row and number of rows of each.
+fn tile_bands(width: usize, height: usize) -> anyhow::Result<Vec<(u8, usize)>> {
+  if width == 0 || width > TILE_COLORS {
+    return Err(anyhow::Error::msg(format!(
+      "A tile frame must be 1 to {} pixels wide, not {}",
+      TILE_COLORS, width
+    )));
+  }
+  if height > u8::MAX as usize {
+    return Err(anyhow::Error::msg(format!(
+      "A tile frame must be at most {} rows high, not {}",
+      u8::MAX,
+      height
+    )));
+  }
+  let rows = TILE_COLORS / width;
+  Ok(
+    (0..height)
+      .step_by(rows)
+      .map(|y| (y as u8, rows.min(height - y)))
+      .collect(),
+  )
+}
+
+/// The zone count, first zone index and colours carried by one zone reply.
+fn zone_chunk(
+  packet: &IncomingPacket,
+  extended: bool,
+) -> anyhow::Result<(usize, usize, Vec<Color>)> {
+  if extended {
+    let state = StateExtendedColorZonesPayload::deserialize(&mut packet.payload())?;
+    Ok((
+      state.zones_count as usize,
+      state.zone_index as usize,
+      state.colors,
+    ))
+  } else {
+    let state = StateMultiZonePayload::deserialize(&mut packet.payload())?;
+    Ok((
+      state.zones_count as usize,
+      state.zone_index as usize,
+      state.colors.to_vec(),
+    ))
+  }
+}
+
+/// Where a client starts counting effect ids, taken from the clock so a
+/// restarted client doesn't reuse the ids of the one before it.
+fn first_effect_id() -> u32 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |elapsed| {
+      (elapsed.as_secs() as u32).wrapping_mul(1000) ^ elapsed.subsec_nanos()
+    })
 }
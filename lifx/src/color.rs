@@ -0,0 +1,275 @@
+//! Client side colour planning: blending, gradients, transitions and palettes.
+//!
+//! Everything here returns plain `Color`s, ready for `Client::set_color`,
+//! `Client::set_zones` or `Client::set_tile_frame`.
+use crate::message::Color;
+
+/// How colours are blended between two end points.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Interpolation {
+  /// Component by component, taking the shorter way round the hue wheel.
+  #[default]
+  Hsbk,
+  /// Through Oklab, which keeps perceived lightness even and avoids muddy midpoints.
+  Oklab,
+}
+
+impl Interpolation {
+  /// The colour `t` of the way from `from` to `to`, with `t` clamped to `0.0..=1.0`.
+  pub fn lerp(self, from: Color, to: Color, t: f32) -> Color {
+    let t = (t as f64).clamp(0.0, 1.0);
+    if t == 0.0 {
+      return from;
+    }
+    if t == 1.0 {
+      return to;
+    }
+    match self {
+      Interpolation::Hsbk => lerp_hsbk(from, to, t),
+      Interpolation::Oklab => lerp_oklab(from, to, t),
+    }
+  }
+
+  /// `count` colours spread evenly across `stops`, starting at the first and ending at the last.
+  ///
+  /// Handy both for the zones of a strip and for cross-fading one bulb through a sequence.
+  pub fn gradient(self, stops: &[Color], count: usize) -> Vec<Color> {
+    match (stops, count) {
+      ([], _) | (_, 0) => vec![],
+      ([only], _) => vec![*only; count],
+      (_, 1) => vec![stops[0]],
+      _ => {
+        let segments = stops.len() - 1;
+        (0..count)
+          .map(|i| {
+            let position = i as f64 / (count - 1) as f64 * segments as f64;
+            let segment = (position as usize).min(segments - 1);
+            let t = position - segment as f64;
+            self.lerp(stops[segment], stops[segment + 1], t as f32)
+          })
+          .collect()
+      }
+    }
+  }
+
+  /// `steps` frames fading every zone of `from` into the matching zone of `to`,
+  /// the last frame being `to` itself.
+  pub fn transition(
+    self,
+    from: &[Color],
+    to: &[Color],
+    steps: usize,
+  ) -> anyhow::Result<Vec<Vec<Color>>> {
+    if from.len() != to.len() {
+      return Err(anyhow::Error::msg(format!(
+        "can't transition {} zone(s) into {}",
+        from.len(),
+        to.len()
+      )));
+    }
+    Ok(
+      (1..=steps)
+        .map(|step| {
+          let t = step as f32 / steps as f32;
+          from
+            .iter()
+            .zip(to)
+            .map(|(from, to)| self.lerp(*from, *to, t))
+            .collect()
+        })
+        .collect(),
+    )
+  }
+}
+
+/// `Interpolation::Hsbk.lerp`.
+pub fn lerp(from: Color, to: Color, t: f32) -> Color {
+  Interpolation::Hsbk.lerp(from, to, t)
+}
+
+/// `Interpolation::Hsbk.gradient`.
+pub fn gradient(stops: &[Color], count: usize) -> Vec<Color> {
+  Interpolation::Hsbk.gradient(stops, count)
+}
+
+/// `Interpolation::Hsbk.transition`.
+pub fn transition(from: &[Color], to: &[Color], steps: usize) -> anyhow::Result<Vec<Vec<Color>>> {
+  Interpolation::Hsbk.transition(from, to, steps)
+}
+
+/// `count` colours with hues spaced evenly round the wheel, starting at `base`.
+pub fn palette(base: Color, count: usize) -> Vec<Color> {
+  (0..count)
+    .map(|i| rotate(base, (i as u64 * 65536 / count as u64) as i64))
+    .collect()
+}
+
+/// `count` neighbouring hues spread across `spread` degrees, centred on `base`.
+pub fn analogous(base: Color, count: usize, spread: f32) -> Vec<Color> {
+  if count < 2 {
+    return vec![base; count];
+  }
+  let spread = spread.clamp(0.0, 360.0) as f64 / 360.0 * 65536.0;
+  (0..count)
+    .map(|i| {
+      let offset = spread * (i as f64 / (count - 1) as f64 - 0.5);
+      rotate(base, offset.round() as i64)
+    })
+    .collect()
+}
+
+/// `base` with its hue turned by `offset` raw levels.
+fn rotate(base: Color, offset: i64) -> Color {
+  let hue = (base.raw_hue() as i64 + offset).rem_euclid(65536) as u16;
+  Color::from_raw(
+    hue,
+    base.raw_saturation(),
+    base.raw_brightness(),
+    base.kelvin(),
+  )
+}
+
+fn lerp_level(from: u16, to: u16, t: f64) -> u16 {
+  (from as f64 + (to as f64 - from as f64) * t).round() as u16
+}
+
+fn lerp_hsbk(from: Color, to: Color, t: f64) -> Color {
+  // an unsaturated end has no meaningful hue, so it takes the other end's
+  let from_hue = if from.raw_saturation() == 0 {
+    to.raw_hue()
+  } else {
+    from.raw_hue()
+  };
+  let to_hue = if to.raw_saturation() == 0 {
+    from_hue
+  } else {
+    to.raw_hue()
+  };
+
+  let mut delta = to_hue as i64 - from_hue as i64;
+  if delta > 32768 {
+    delta -= 65536;
+  } else if delta < -32768 {
+    delta += 65536;
+  }
+  let hue = (from_hue as i64 + (delta as f64 * t).round() as i64).rem_euclid(65536) as u16;
+
+  Color::from_raw(
+    hue,
+    lerp_level(from.raw_saturation(), to.raw_saturation(), t),
+    lerp_level(from.raw_brightness(), to.raw_brightness(), t),
+    lerp_level(from.kelvin(), to.kelvin(), t),
+  )
+}
+
+fn lerp_oklab(from: Color, to: Color, t: f64) -> Color {
+  let from_lab = to_oklab(from.to_linear_rgb());
+  let to_lab = to_oklab(to.to_linear_rgb());
+  let mix = |a: f64, b: f64| a + (b - a) * t;
+  let (red, green, blue) = from_oklab((
+    mix(from_lab.0, to_lab.0),
+    mix(from_lab.1, to_lab.1),
+    mix(from_lab.2, to_lab.2),
+  ));
+  Color::from_linear_rgb(red, green, blue, lerp_level(from.kelvin(), to.kelvin(), t))
+}
+
+fn to_oklab((red, green, blue): (f64, f64, f64)) -> (f64, f64, f64) {
+  let l = (0.412_221_470_8 * red + 0.536_332_536_3 * green + 0.051_445_992_9 * blue).cbrt();
+  let m = (0.211_903_498_2 * red + 0.680_699_545_1 * green + 0.107_396_956_6 * blue).cbrt();
+  let s = (0.088_302_461_9 * red + 0.281_718_837_6 * green + 0.629_978_700_5 * blue).cbrt();
+  (
+    0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s,
+    1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s,
+    0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s,
+  )
+}
+
+fn from_oklab((lightness, a, b): (f64, f64, f64)) -> (f64, f64, f64) {
+  let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+  let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+  let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+  (
+    4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s,
+    -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s,
+    -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_lerp_the_short_way_round() {
+    let from = Color::from_hsbk_f32(350.0, 1.0, 1.0, 3500);
+    let to = Color::from_hsbk_f32(10.0, 1.0, 1.0, 3500);
+    assert_eq!(lerp(from, to, 0.0), from);
+    assert_eq!(lerp(from, to, 1.0), to);
+    assert_eq!(lerp(from, to, 0.5).raw_hue(), 0);
+    assert_eq!(lerp(to, from, 0.5).raw_hue(), 0);
+  }
+  #[test]
+  fn should_keep_hue_when_fading_from_white() {
+    let white = Color::from_raw(0, 0, 65535, 2500);
+    let blue = Color::from_raw(43690, 65535, 65535, 4500);
+    let middle = lerp(white, blue, 0.5);
+    assert_eq!(middle, Color::from_raw(43690, 32768, 65535, 3500));
+  }
+  #[test]
+  fn should_lerp_through_oklab() {
+    let black = Color::from_rgb(0, 0, 0);
+    let white = Color::from_rgb(255, 255, 255);
+    let grey = Interpolation::Oklab.lerp(black, white, 0.5);
+    assert_eq!(grey.raw_saturation(), 0);
+    // half of Oklab lightness is much brighter than half the linear light
+    assert_eq!(grey.to_rgb(), (99, 99, 99));
+
+    let red = Color::from_rgb(255, 0, 0);
+    assert_eq!(Interpolation::Oklab.lerp(red, white, 1.0), white);
+  }
+  #[test]
+  fn should_build_gradients() {
+    let red = Color::from_raw(0, 65535, 65535, 3500);
+    let green = Color::from_raw(21845, 65535, 65535, 3500);
+    let blue = Color::from_raw(43690, 65535, 65535, 3500);
+
+    let zones = gradient(&[red, green, blue], 5);
+    assert_eq!(zones.len(), 5);
+    assert_eq!(zones[0], red);
+    assert_eq!(zones[2], green);
+    assert_eq!(zones[4], blue);
+    assert_eq!(zones[1].raw_hue(), 10923);
+
+    assert_eq!(gradient(&[red], 3), vec![red; 3]);
+    assert_eq!(gradient(&[red, blue], 1), vec![red]);
+    assert!(gradient(&[], 3).is_empty());
+  }
+  #[test]
+  fn should_plan_transitions() {
+    let off = vec![Color::from_raw(0, 0, 0, 3500); 3];
+    let on = vec![Color::from_raw(0, 0, 65535, 3500); 3];
+    let frames = transition(&off, &on, 4).unwrap();
+    assert_eq!(frames.len(), 4);
+    assert_eq!(frames[0][0].raw_brightness(), 16384);
+    assert_eq!(frames[3], on);
+    assert!(transition(&off, &on[..2], 4).is_err());
+  }
+  #[test]
+  fn should_build_palettes() {
+    let base = Color::from_raw(1000, 65535, 32768, 3500);
+    let hues = palette(base, 4)
+      .iter()
+      .map(Color::raw_hue)
+      .collect::<Vec<_>>();
+    assert_eq!(hues, vec![1000, 17384, 33768, 50152]);
+
+    let hues = analogous(base, 3, 90.0)
+      .iter()
+      .map(Color::raw_hue)
+      .collect::<Vec<_>>();
+    assert_eq!(hues, vec![58344, 1000, 9192]);
+    assert!(analogous(base, 3, 90.0)
+      .iter()
+      .all(|color| color.raw_brightness() == 32768));
+  }
+}
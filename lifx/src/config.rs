@@ -0,0 +1,100 @@
+use if_addrs::IfAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// The port LIFX devices listen on.
+pub const PORT: u16 = 56700;
+
+/// Where a `Client` sends packets for devices it hasn't heard from yet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+  /// Broadcast addresses that discovery and untargeted requests are sent to.
+  pub broadcast: Vec<IpAddr>,
+  /// The port devices listen on, for every broadcast address.
+  pub port: u16,
+  /// Devices to reach directly, for networks that drop broadcast traffic.
+  pub peers: Vec<SocketAddr>,
+}
+
+impl ClientConfig {
+  pub fn new(broadcast: Vec<IpAddr>, port: u16, peers: Vec<SocketAddr>) -> Self {
+    Self {
+      broadcast,
+      port,
+      peers,
+    }
+  }
+
+  /// Broadcasts to the subnet of every IPv4 interface, rather than to `255.255.255.255`.
+  ///
+  /// Useful on hosts with several interfaces, where the limited broadcast
+  /// address only goes out of one of them.
+  pub fn from_interfaces() -> anyhow::Result<Self> {
+    let mut broadcast = vec![];
+    for interface in if_addrs::get_if_addrs()? {
+      if interface.is_loopback() {
+        continue;
+      }
+      if let IfAddr::V4(addr) = interface.addr {
+        if let Some(addr) = addr.broadcast {
+          broadcast.push(IpAddr::V4(addr));
+        }
+      }
+    }
+    if broadcast.is_empty() {
+      return Err(anyhow::Error::msg(
+        "No interface has an IPv4 broadcast address",
+      ));
+    }
+    Ok(Self {
+      broadcast,
+      ..Self::default()
+    })
+  }
+
+  /// Every address a packet goes to when the device's own address isn't known.
+  pub(crate) fn destinations(&self) -> Vec<SocketAddr> {
+    self
+      .broadcast
+      .iter()
+      .map(|ip| SocketAddr::new(*ip, self.port))
+      .chain(self.peers.iter().copied())
+      .collect()
+  }
+}
+
+impl Default for ClientConfig {
+  fn default() -> Self {
+    Self {
+      broadcast: vec![IpAddr::V4(Ipv4Addr::BROADCAST)],
+      port: PORT,
+      peers: vec![],
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_broadcast_to_default_port() {
+    assert_eq!(
+      ClientConfig::default().destinations(),
+      vec!["255.255.255.255:56700".parse::<SocketAddr>().unwrap()]
+    );
+  }
+  #[test]
+  fn should_include_peers() {
+    let config = ClientConfig::new(
+      vec!["192.168.1.255".parse().unwrap()],
+      56800,
+      vec!["10.0.0.5:56700".parse().unwrap()],
+    );
+    assert_eq!(
+      config.destinations(),
+      vec![
+        "192.168.1.255:56800".parse::<SocketAddr>().unwrap(),
+        "10.0.0.5:56700".parse().unwrap()
+      ]
+    );
+  }
+}
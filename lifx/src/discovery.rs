@@ -0,0 +1,217 @@
+use crate::client::Client;
+use crate::light::Light;
+use crate::message::{EmptyPayload, StateServicePayload};
+use crate::proto::{Deserializable, MessageType};
+use crate::target::Target;
+use log::warn;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// How many discovery rounds a device can miss before it is reported as lost.
+const MISSED_ROUNDS: u32 = 3;
+
+/// A device that answered a `GetService` broadcast.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Device {
+  pub serial: [u8; 6],
+  pub ip: IpAddr,
+  pub port: u32,
+  pub service: u8,
+}
+
+impl Device {
+  pub fn target(&self) -> Target {
+    Target::Serial(self.serial)
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DiscoveryEvent {
+  DeviceAppeared(Device),
+  DeviceLost(Device),
+}
+
+/// Broadcasts `GetService` and returns every device that replied within `timeout`.
+pub async fn discover(client: &Client, timeout: Duration) -> anyhow::Result<Vec<Device>> {
+  let responses = client
+    .collect(
+      MessageType::GetService,
+      EmptyPayload {},
+      MessageType::StateService,
+      timeout,
+    )
+    .await?;
+
+  let mut devices: HashMap<u64, Device> = HashMap::new();
+  for (addr, packet) in responses {
+    let serial = match Target::from(packet.target()).serial() {
+      Some(serial) => serial,
+      None => continue,
+    };
+    let service = match StateServicePayload::deserialize(&mut packet.payload()) {
+      Ok(service) => service,
+      Err(err) => {
+        warn!("ignoring malformed service from {}: {}", addr, err);
+        continue;
+      }
+    };
+    devices.entry(packet.target()).or_insert(Device {
+      serial,
+      ip: addr.ip(),
+      port: service.port,
+      service: service.service,
+    });
+  }
+
+  Ok(devices.into_values().collect())
+}
+
+/// Discovers devices and connects a `Light` to each, skipping any that stop answering.
+pub async fn lights(client: &Arc<Client>, timeout: Duration) -> anyhow::Result<Vec<Light>> {
+  let mut lights = vec![];
+  for device in discover(client, timeout).await? {
+    match Light::connect(client.clone(), device).await {
+      Ok(light) => lights.push(light),
+      Err(err) => warn!("ignoring {}: {}", device.target(), err),
+    }
+  }
+  Ok(lights)
+}
+
+/// Events reported by `watch`. Discovery stops once this is dropped.
+pub struct Watch {
+  events: mpsc::UnboundedReceiver<DiscoveryEvent>,
+  _watching: oneshot::Receiver<()>,
+}
+
+impl Watch {
+  /// The next device to appear or disappear.
+  pub async fn recv(&mut self) -> Option<DiscoveryEvent> {
+    self.events.recv().await
+  }
+}
+
+/// Runs discovery every `interval`, reporting devices as they appear and disappear.
+///
+/// A device is only reported lost after missing several rounds in a row, so a
+/// single dropped reply doesn't make it flap.
+pub fn watch(client: Arc<Client>, interval: Duration) -> Watch {
+  let (sender, events) = mpsc::unbounded_channel();
+  let (mut watching, watched) = oneshot::channel::<()>();
+  tokio::spawn(async move {
+    let mut known: HashMap<[u8; 6], (Device, u32)> = HashMap::new();
+    loop {
+      let round = async {
+        match discover(&client, interval).await {
+          Ok(devices) => Some(devices),
+          Err(err) => {
+            warn!("discovery failed: {}", err);
+            tokio::time::delay_for(interval).await;
+            None
+          }
+        }
+      };
+      // rounds without changes send nothing, so the watch is checked for drops directly
+      let devices = tokio::select! {
+        devices = round => devices,
+        _ = watching.closed() => break,
+      };
+      if let Some(devices) = devices {
+        if !track(&mut known, devices, &sender) {
+          break;
+        }
+      }
+    }
+  });
+  Watch {
+    events,
+    _watching: watched,
+  }
+}
+
+/// Updates the known devices with the latest round, returning false once nobody is listening.
+fn track(
+  known: &mut HashMap<[u8; 6], (Device, u32)>,
+  devices: Vec<Device>,
+  sender: &mpsc::UnboundedSender<DiscoveryEvent>,
+) -> bool {
+  let mut events = vec![];
+  for (_, missed) in known.values_mut() {
+    *missed += 1;
+  }
+  for device in devices {
+    match known.insert(device.serial, (device, 0)) {
+      Some((previous, _)) if previous == device => {}
+      Some((previous, _)) => {
+        events.push(DiscoveryEvent::DeviceLost(previous));
+        events.push(DiscoveryEvent::DeviceAppeared(device));
+      }
+      None => events.push(DiscoveryEvent::DeviceAppeared(device)),
+    }
+  }
+  known.retain(|_, (device, missed)| {
+    if *missed >= MISSED_ROUNDS {
+      events.push(DiscoveryEvent::DeviceLost(*device));
+      false
+    } else {
+      true
+    }
+  });
+
+  events.into_iter().all(|event| sender.send(event).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn device(last: u8) -> Device {
+    Device {
+      serial: [0xd0, 0x73, 0xd5, 0, 0, last],
+      ip: "192.168.1.5".parse().unwrap(),
+      port: 56700,
+      service: 1,
+    }
+  }
+
+  #[test]
+  fn should_report_appeared_and_lost_devices() {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut known = HashMap::new();
+
+    assert!(track(&mut known, vec![device(1), device(2)], &sender));
+    let mut appeared = vec![receiver.try_recv().unwrap(), receiver.try_recv().unwrap()];
+    appeared.sort_by_key(|event| match event {
+      DiscoveryEvent::DeviceAppeared(device) => device.serial,
+      DiscoveryEvent::DeviceLost(device) => device.serial,
+    });
+    assert_eq!(
+      appeared,
+      vec![
+        DiscoveryEvent::DeviceAppeared(device(1)),
+        DiscoveryEvent::DeviceAppeared(device(2))
+      ]
+    );
+
+    for _ in 0..MISSED_ROUNDS - 1 {
+      assert!(track(&mut known, vec![device(1)], &sender));
+      assert!(receiver.try_recv().is_err());
+    }
+
+    assert!(track(&mut known, vec![device(1)], &sender));
+    assert_eq!(
+      receiver.try_recv().unwrap(),
+      DiscoveryEvent::DeviceLost(device(2))
+    );
+  }
+
+  #[test]
+  fn should_stop_when_nobody_is_listening() {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    drop(receiver);
+    assert!(!track(&mut HashMap::new(), vec![device(1)], &sender));
+  }
+}
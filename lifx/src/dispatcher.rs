@@ -0,0 +1,314 @@
+use crate::message::StateServicePayload;
+use crate::proto::{Deserializable, IncomingPacket, MessageType};
+use crate::reader::Reader;
+use log::{trace, warn};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+
+pub(crate) type Response = (SocketAddr, IncomingPacket);
+
+const UDP_SERVICE: u8 = 1;
+
+struct Pending {
+  target: u64,
+  sender: mpsc::UnboundedSender<Response>,
+}
+
+/// Requests that are waiting on a reply, keyed by sequence number.
+#[derive(Clone, Default)]
+pub(crate) struct InFlight {
+  pending: Arc<Mutex<HashMap<u8, Pending>>>,
+}
+
+impl InFlight {
+  /// Takes the next sequence from `sequences` that no other request is waiting on.
+  ///
+  /// Sequences wrap after 256 requests, so one still in flight is skipped rather
+  /// than having its replies handed to the newer request.
+  pub async fn register(
+    &self,
+    sequences: &AtomicU8,
+    target: u64,
+  ) -> anyhow::Result<(u8, mpsc::UnboundedReceiver<Response>)> {
+    let mut pending = self.pending.lock().await;
+    for _ in 0..=u8::MAX {
+      let sequence = sequences.fetch_add(1, Ordering::Relaxed);
+      if pending.contains_key(&sequence) {
+        continue;
+      }
+      let (sender, receiver) = mpsc::unbounded_channel();
+      pending.insert(sequence, Pending { target, sender });
+      return Ok((sequence, receiver));
+    }
+    Err(anyhow::Error::msg(
+      "Every sequence number is taken by a request in flight",
+    ))
+  }
+
+  pub async fn remove(&self, sequence: u8) {
+    let mut pending = self.pending.lock().await;
+    pending.remove(&sequence);
+  }
+
+  /// Hands the packet to the waiting request, giving it back when nobody claims it.
+  async fn route(&self, response: Response) -> Option<Response> {
+    let mut pending = self.pending.lock().await;
+    let sequence = response.1.sequence();
+    let target = response.1.target();
+    match pending.get(&sequence) {
+      Some(waiting) if waiting.target == 0 || waiting.target == target => {
+        match waiting.sender.send(response) {
+          Ok(()) => None,
+          Err(mpsc::error::SendError(response)) => {
+            // the caller has gone away, stop tracking it
+            pending.remove(&sequence);
+            Some(response)
+          }
+        }
+      }
+      _ => Some(response),
+    }
+  }
+}
+
+/// Where each device was last heard from, keyed by target.
+#[derive(Clone, Default)]
+pub(crate) struct Addresses {
+  known: Arc<Mutex<HashMap<u64, SocketAddr>>>,
+}
+
+impl Addresses {
+  pub async fn get(&self, target: u64) -> Option<SocketAddr> {
+    let known = self.known.lock().await;
+    known.get(&target).copied()
+  }
+
+  /// Records where a device replied from. Requests are skipped, since the
+  /// target of a request names the device it was sent to, not the sender.
+  async fn learn(&self, addr: SocketAddr, packet: &IncomingPacket) {
+    let target = packet.target();
+    if target == 0 || !packet.message_type().is_ok_and(MessageType::is_reply) {
+      return;
+    }
+
+    let mut addr = addr;
+    if packet.message_type() == Ok(MessageType::StateService) {
+      // devices advertise the port they listen on, which may not be the one they replied from
+      match StateServicePayload::deserialize(&mut packet.payload()) {
+        Ok(service) if service.service == UDP_SERVICE => addr.set_port(service.port as u16),
+        _ => return,
+      }
+    }
+
+    let mut known = self.known.lock().await;
+    known.insert(target, addr);
+  }
+}
+
+pub(crate) struct Dispatcher {
+  source: u32,
+  reader: Reader,
+  in_flight: InFlight,
+  addresses: Addresses,
+  unsolicited: broadcast::Sender<Response>,
+}
+
+impl Dispatcher {
+  pub fn new(
+    source: u32,
+    reader: Reader,
+    in_flight: InFlight,
+    addresses: Addresses,
+    unsolicited: broadcast::Sender<Response>,
+  ) -> Self {
+    Self {
+      source,
+      reader,
+      in_flight,
+      addresses,
+      unsolicited,
+    }
+  }
+
+  pub async fn run(mut self, mut shutdown: oneshot::Receiver<()>) {
+    loop {
+      let result = tokio::select! {
+        result = self.reader.read_packet() => result,
+        _ = &mut shutdown => break,
+      };
+
+      let (addr, packet) = match result {
+        Ok(response) => response,
+        Err(err) => {
+          warn!("unable to read packet: {}", err);
+          continue;
+        }
+      };
+
+      self.addresses.learn(addr, &packet).await;
+
+      let response = if packet.source() == self.source {
+        self.in_flight.route((addr, packet)).await
+      } else {
+        Some((addr, packet))
+      };
+
+      if let Some(response) = response {
+        trace!(
+          "unsolicited type {} from {}",
+          response.1.raw_message_type(),
+          response.0
+        );
+        // nobody listening for unsolicited packets is not an error
+        let _ = self.unsolicited.send(response);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::message::EmptyPayload;
+  use crate::proto::{Deserializable, MessageType, OutgoingPacket};
+  use bytes::Bytes;
+  use std::convert::TryInto;
+
+  fn response(sequence: u8, target: u64) -> Response {
+    let packet = OutgoingPacket::new(
+      sequence,
+      1337,
+      target,
+      false,
+      false,
+      MessageType::State,
+      EmptyPayload {},
+    )
+    .unwrap();
+    let bytes: Vec<u8> = packet.try_into().unwrap();
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    ("127.0.0.1:56700".parse().unwrap(), packet)
+  }
+
+  #[tokio::test]
+  async fn should_route_to_matching_request() {
+    let in_flight = InFlight::default();
+    let (_, mut receiver) = in_flight.register(&AtomicU8::new(5), 0).await.unwrap();
+
+    assert!(in_flight.route(response(5, 0xd073d5)).await.is_none());
+    assert!(in_flight.route(response(6, 0xd073d5)).await.is_some());
+
+    let (_, packet) = receiver.recv().await.unwrap();
+    assert_eq!(packet.sequence(), 5);
+  }
+
+  #[tokio::test]
+  async fn should_not_route_other_targets() {
+    let in_flight = InFlight::default();
+    let _receiver = in_flight
+      .register(&AtomicU8::new(5), 0xd073d5)
+      .await
+      .unwrap();
+    assert!(in_flight.route(response(5, 0xd073d6)).await.is_some());
+  }
+
+  #[tokio::test]
+  async fn should_release_abandoned_requests() {
+    let in_flight = InFlight::default();
+    drop(in_flight.register(&AtomicU8::new(5), 0).await.unwrap());
+    assert!(in_flight.route(response(5, 0)).await.is_some());
+  }
+
+  #[tokio::test]
+  async fn should_skip_sequences_in_flight_when_wrapping() {
+    let in_flight = InFlight::default();
+    let sequences = AtomicU8::new(5);
+    let (first, mut oldest) = in_flight.register(&sequences, 0).await.unwrap();
+    assert_eq!(first, 5);
+
+    let mut newer = vec![];
+    for _ in 0..255 {
+      newer.push(in_flight.register(&sequences, 0).await.unwrap());
+    }
+    assert!(newer.iter().all(|(sequence, _)| *sequence != 5));
+    assert!(in_flight.register(&sequences, 0).await.is_err());
+
+    // the oldest request still gets its own replies
+    assert!(in_flight.route(response(5, 0xd073d5)).await.is_none());
+    assert_eq!(oldest.recv().await.unwrap().1.sequence(), 5);
+
+    in_flight.remove(6).await;
+    let (sequence, _) = in_flight.register(&sequences, 0).await.unwrap();
+    assert_eq!(sequence, 6);
+  }
+
+  #[tokio::test]
+  async fn should_learn_advertised_port() {
+    struct Service;
+    impl crate::proto::Serializable for Service {
+      fn serialize(&self, bytes: &mut bytes::BytesMut) -> anyhow::Result<()> {
+        bytes.extend_from_slice(&[0x1, 0x7d, 0xdd, 0x0, 0x0]);
+        Ok(())
+      }
+    }
+    let packet = OutgoingPacket::new(
+      0,
+      1,
+      0xd073d5,
+      false,
+      false,
+      MessageType::StateService,
+      Service,
+    )
+    .unwrap();
+    let bytes: Vec<u8> = packet.try_into().unwrap();
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+
+    let addresses = Addresses::default();
+    addresses
+      .learn("192.168.1.5:56700".parse().unwrap(), &packet)
+      .await;
+    assert_eq!(
+      addresses.get(0xd073d5).await,
+      Some("192.168.1.5:56701".parse().unwrap())
+    );
+    assert_eq!(addresses.get(0).await, None);
+  }
+
+  #[tokio::test]
+  async fn should_not_learn_from_requests() {
+    let addresses = Addresses::default();
+    let (_, reply) = response(0, 0xd073d5);
+    addresses
+      .learn("192.168.1.5:56700".parse().unwrap(), &reply)
+      .await;
+
+    // another controller on the LAN telling the bulb to change colour
+    let packet = OutgoingPacket::new(
+      0,
+      42,
+      0xd073d5,
+      true,
+      false,
+      MessageType::SetColor,
+      crate::message::SetColorPayload {
+        color: crate::message::Color::from_raw(0, 0, 0, 3500),
+        duration: 0,
+      },
+    )
+    .unwrap();
+    let bytes: Vec<u8> = packet.try_into().unwrap();
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    addresses
+      .learn("192.168.1.9:56700".parse().unwrap(), &packet)
+      .await;
+
+    assert_eq!(
+      addresses.get(0xd073d5).await,
+      Some("192.168.1.5:56700".parse().unwrap())
+    );
+  }
+}
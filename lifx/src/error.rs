@@ -0,0 +1,123 @@
+use crate::proto::MessageType;
+use crate::target::Target;
+use std::fmt;
+use std::time::Duration;
+
+/// Returned when a request ran out of retries before its reply or acknowledgement arrived.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeoutError {
+  pub message_type: MessageType,
+  pub attempts: u32,
+  pub elapsed: Duration,
+}
+
+impl fmt::Display for TimeoutError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} timed out after {} attempt(s) in {:?}",
+      self.message_type, self.attempts, self.elapsed
+    )
+  }
+}
+
+impl std::error::Error for TimeoutError {}
+
+/// Returned instead of sending a command the device's product can't carry out.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UnsupportedError {
+  pub target: Target,
+  pub product: &'static str,
+  pub feature: &'static str,
+}
+
+impl fmt::Display for UnsupportedError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} ({}) doesn't support {}",
+      self.target, self.product, self.feature
+    )
+  }
+}
+
+impl std::error::Error for UnsupportedError {}
+
+/// Returned when a datagram or payload can't be decoded.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DecodeError {
+  /// Fewer bytes were left than the field being read needs.
+  Truncated { needed: usize, remaining: usize },
+  /// The header names a message type this crate doesn't know.
+  UnknownMessageType(u16),
+  /// The size in the header doesn't match the length of the datagram.
+  SizeMismatch { declared: u16, actual: usize },
+}
+
+impl fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DecodeError::Truncated { needed, remaining } => write!(
+        f,
+        "truncated, needed {} byte(s) but only {} remain",
+        needed, remaining
+      ),
+      DecodeError::UnknownMessageType(message_type) => {
+        write!(f, "unknown message type {}", message_type)
+      }
+      DecodeError::SizeMismatch { declared, actual } => write!(
+        f,
+        "header declares {} byte(s) but the datagram is {}",
+        declared, actual
+      ),
+    }
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Returned when a string can't be parsed as a `Color`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseColorError {
+  /// There was nothing but whitespace to parse.
+  Empty,
+  /// A word that is neither a colour name nor a `component:value` pair.
+  UnknownToken(String),
+  /// The value given to a component isn't a number it accepts.
+  InvalidNumber {
+    component: &'static str,
+    value: String,
+  },
+  /// The value given to a component is outside the range it accepts.
+  OutOfRange {
+    component: &'static str,
+    value: f64,
+    min: f64,
+    max: f64,
+  },
+  /// A hex colour that isn't six hex digits.
+  InvalidHex(String),
+}
+
+impl fmt::Display for ParseColorError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ParseColorError::Empty => write!(f, "no colour given"),
+      ParseColorError::UnknownToken(token) => write!(f, "unknown colour {:?}", token),
+      ParseColorError::InvalidNumber { component, value } => {
+        write!(f, "{:?} is not a valid {}", value, component)
+      }
+      ParseColorError::OutOfRange {
+        component,
+        value,
+        min,
+        max,
+      } => write!(f, "{} {} is outside {}..={}", component, value, min, max),
+      ParseColorError::InvalidHex(hex) => {
+        write!(f, "{:?} is not a hex colour like #ff8800", hex)
+      }
+    }
+  }
+}
+
+impl std::error::Error for ParseColorError {}
@@ -1,8 +1,26 @@
 mod client;
+pub mod color;
+mod config;
+pub mod discovery;
+mod dispatcher;
+mod error;
+mod light;
 mod message;
+mod product;
 mod proto;
 mod reader;
+mod retry;
+mod target;
 mod writer;
 pub use client::Client;
+pub use config::{ClientConfig, PORT};
+pub use error::{DecodeError, ParseColorError, TimeoutError, UnsupportedError};
+pub use light::Light;
 pub use message::*;
-pub use proto::{MessageType, Power};
+pub use product::{Capabilities, Features, Product, Upgrade};
+pub use proto::{
+  Decoders, Deserializable, Header, IncomingPacket, Message, MessageType, OutgoingPacket,
+  OutgoingPacketBuilder, Power, Serializable,
+};
+pub use retry::RetryPolicy;
+pub use target::Target;
@@ -0,0 +1,133 @@
+use crate::client::Client;
+use crate::discovery::Device;
+use crate::message::{Color, GroupPayload, Label, LocationPayload, StatePayload};
+use crate::product::Capabilities;
+use crate::proto::Power;
+use crate::target::Target;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// A handle on one device that remembers who it is and what it last reported.
+///
+/// Every handle talks through the `Client` it was made from, so any number of
+/// them share one socket. The cached state is only as fresh as the last
+/// `refresh` or change made through this handle.
+#[derive(Clone)]
+pub struct Light {
+  client: Arc<Client>,
+  serial: [u8; 6],
+  address: SocketAddr,
+  group: GroupPayload,
+  location: LocationPayload,
+  capabilities: Option<Capabilities>,
+  state: StatePayload,
+}
+
+impl Light {
+  /// Reads the identity and state of a discovered device.
+  pub async fn connect(client: Arc<Client>, device: Device) -> anyhow::Result<Light> {
+    let target = device.target();
+    let capabilities = client.capabilities(target).await?;
+    let group = client.get_group(target).await?;
+    let location = client.get_location(target).await?;
+    let state = client.get_state(target).await?;
+    Ok(Light {
+      client,
+      serial: device.serial,
+      address: SocketAddr::new(device.ip, device.port as u16),
+      group,
+      location,
+      capabilities,
+      state,
+    })
+  }
+
+  pub fn serial(&self) -> [u8; 6] {
+    self.serial
+  }
+
+  pub fn target(&self) -> Target {
+    Target::Serial(self.serial)
+  }
+
+  pub fn address(&self) -> SocketAddr {
+    self.address
+  }
+
+  pub fn label(&self) -> &Label {
+    &self.state.label
+  }
+
+  pub fn group(&self) -> &GroupPayload {
+    &self.group
+  }
+
+  pub fn location(&self) -> &LocationPayload {
+    &self.location
+  }
+
+  /// `None` when the product isn't in the registry.
+  pub fn capabilities(&self) -> Option<&Capabilities> {
+    self.capabilities.as_ref()
+  }
+
+  /// The state the device last reported, updated by changes made through this handle.
+  pub fn state(&self) -> &StatePayload {
+    &self.state
+  }
+
+  pub fn color(&self) -> Color {
+    self.state.color
+  }
+
+  pub fn is_on(&self) -> bool {
+    self.state.power != u16::from(Power::Off)
+  }
+
+  /// Re-reads the device's state, label included.
+  pub async fn refresh(&mut self) -> anyhow::Result<&StatePayload> {
+    self.state = self.client.get_state(self.target()).await?;
+    Ok(&self.state)
+  }
+
+  /// Re-reads the group and location the device belongs to.
+  pub async fn refresh_membership(&mut self) -> anyhow::Result<()> {
+    self.group = self.client.get_group(self.target()).await?;
+    self.location = self.client.get_location(self.target()).await?;
+    Ok(())
+  }
+
+  pub async fn set_color(&mut self, color: Color, duration: u32) -> anyhow::Result<()> {
+    self
+      .client
+      .set_color(self.target(), color, duration)
+      .await?;
+    self.state.color = self
+      .capabilities
+      .map_or(color, |capabilities| capabilities.restrict(color));
+    Ok(())
+  }
+
+  pub async fn set_power(&mut self, power: Power, duration: u32) -> anyhow::Result<()> {
+    self
+      .client
+      .set_power(self.target(), power, duration)
+      .await?;
+    self.state.power = power.into();
+    Ok(())
+  }
+
+  /// Turns the light off when it was last seen on, and on otherwise, returning the new power.
+  pub async fn toggle(&mut self, duration: u32) -> anyhow::Result<Power> {
+    let power = if self.is_on() { Power::Off } else { Power::On };
+    self.set_power(power, duration).await?;
+    Ok(power)
+  }
+
+  pub async fn set_label(&mut self, label: impl Into<Label>) -> anyhow::Result<()> {
+    let label = label.into();
+    self.client.set_label(self.target(), label.clone()).await?;
+    self.state.label = label;
+    Ok(())
+  }
+}
@@ -1,5 +1,19 @@
+use crate::error::ParseColorError;
 use std::fmt;
+use std::str::FromStr;
 
+/// The warmest white most LIFX bulbs can produce.
+pub const MIN_KELVIN: u16 = 1500;
+/// The coolest white most LIFX bulbs can produce.
+pub const MAX_KELVIN: u16 = 9000;
+/// The kelvin given to colours built from RGB, hex or xy, which don't carry one.
+pub const DEFAULT_KELVIN: u16 = 3500;
+
+/// The xy chromaticity of D65, sRGB's white point.
+const WHITE_POINT: (f64, f64) = (0.3127, 0.3290);
+
+/// Hue, saturation, brightness and kelvin, stored as the raw levels a device uses.
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Color {
   pub(crate) hue: u16,
   pub(crate) saturation: u16,
@@ -8,6 +22,186 @@ pub struct Color {
 }
 
 impl Color {
+  /// Builds a colour from the device's raw levels, without any rounding.
+  pub const fn from_raw(hue: u16, saturation: u16, brightness: u16, kelvin: u16) -> Color {
+    Color {
+      hue,
+      saturation,
+      brightness,
+      kelvin,
+    }
+  }
+
+  /// `hue` is in degrees and wraps around, `saturation` and `brightness` are
+  /// clamped to `0.0..=1.0` and `kelvin` to the range bulbs support.
+  pub fn from_hsbk_f32(hue: f32, saturation: f32, brightness: f32, kelvin: u16) -> Color {
+    Color {
+      hue: hue_to_raw(hue as f64),
+      saturation: unit_to_raw(saturation as f64),
+      brightness: unit_to_raw(brightness as f64),
+      kelvin: clamp_kelvin(kelvin, MIN_KELVIN, MAX_KELVIN),
+    }
+  }
+
+  /// The inverse of `from_hsbk_f32`, converting back to the same raw levels.
+  pub fn to_hsbk_f32(&self) -> (f32, f32, f32, u16) {
+    (
+      (self.hue as f64 * 360.0 / 65536.0) as f32,
+      (self.saturation as f64 / 65535.0) as f32,
+      (self.brightness as f64 / 65535.0) as f32,
+      self.kelvin,
+    )
+  }
+
+  /// A colour from 8 bit sRGB, given `DEFAULT_KELVIN`.
+  pub fn from_rgb(red: u8, green: u8, blue: u8) -> Color {
+    let (hue, saturation, value) = rgb_to_hsv(
+      red as f64 / 255.0,
+      green as f64 / 255.0,
+      blue as f64 / 255.0,
+    );
+    Color {
+      hue: hue_to_raw(hue),
+      saturation: unit_to_raw(saturation),
+      brightness: unit_to_raw(value),
+      kelvin: DEFAULT_KELVIN,
+    }
+  }
+
+  /// The colour in 8 bit sRGB. Kelvin is ignored, so whites come out neutral.
+  pub fn to_rgb(&self) -> (u8, u8, u8) {
+    let (red, green, blue) = self.rgb_f64();
+    (
+      (red * 255.0).round() as u8,
+      (green * 255.0).round() as u8,
+      (blue * 255.0).round() as u8,
+    )
+  }
+
+  /// Parses `#rrggbb` or `rrggbb`.
+  pub fn from_hex(hex: &str) -> Result<Color, ParseColorError> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+      return Err(ParseColorError::InvalidHex(hex.to_owned()));
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default();
+    Ok(Color::from_rgb(channel(0), channel(2), channel(4)))
+  }
+
+  /// The colour as `#rrggbb`.
+  pub fn to_hex(&self) -> String {
+    let (red, green, blue) = self.to_rgb();
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+  }
+
+  /// A colour from CIE 1931 xy chromaticity, with `brightness` in `0.0..=1.0`.
+  pub fn from_xy(x: f32, y: f32, brightness: f32) -> Color {
+    let (x, y) = (x as f64, y as f64);
+    let (red, green, blue) = if y <= 0.0 {
+      (1.0, 1.0, 1.0)
+    } else {
+      let (big_x, big_y, big_z) = (x / y, 1.0, (1.0 - x - y) / y);
+      let red = 3.2406 * big_x - 1.5372 * big_y - 0.4986 * big_z;
+      let green = -0.9689 * big_x + 1.8758 * big_y + 0.0415 * big_z;
+      let blue = 0.0557 * big_x - 0.2040 * big_y + 1.0570 * big_z;
+      // out of gamut colours are pulled back to the nearest edge
+      let (red, green, blue) = (red.max(0.0), green.max(0.0), blue.max(0.0));
+      let max = red.max(green).max(blue).max(f64::EPSILON);
+      (
+        gamma_encode(red / max),
+        gamma_encode(green / max),
+        gamma_encode(blue / max),
+      )
+    };
+    let (hue, saturation, _) = rgb_to_hsv(red, green, blue);
+    Color {
+      hue: hue_to_raw(hue),
+      saturation: unit_to_raw(saturation),
+      brightness: unit_to_raw(brightness as f64),
+      kelvin: DEFAULT_KELVIN,
+    }
+  }
+
+  /// The CIE 1931 xy chromaticity of the colour, ignoring brightness and kelvin.
+  pub fn to_xy(&self) -> (f32, f32) {
+    let full = Color {
+      brightness: u16::MAX,
+      ..*self
+    };
+    let (red, green, blue) = full.rgb_f64();
+    let (red, green, blue) = (gamma_decode(red), gamma_decode(green), gamma_decode(blue));
+    let big_x = 0.4124 * red + 0.3576 * green + 0.1805 * blue;
+    let big_y = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+    let big_z = 0.0193 * red + 0.1192 * green + 0.9505 * blue;
+    let sum = big_x + big_y + big_z;
+    if sum <= 0.0 {
+      return (WHITE_POINT.0 as f32, WHITE_POINT.1 as f32);
+    }
+    ((big_x / sum) as f32, (big_y / sum) as f32)
+  }
+
+  /// A white at the colour temperature given in mireds, with `brightness` in `0.0..=1.0`.
+  pub fn from_mireds(mireds: u16, brightness: f32) -> Color {
+    let kelvin = 1_000_000 / (mireds.max(1) as u32);
+    Color {
+      hue: 0,
+      saturation: 0,
+      brightness: unit_to_raw(brightness as f64),
+      kelvin: clamp_kelvin(kelvin.min(u16::MAX as u32) as u16, MIN_KELVIN, MAX_KELVIN),
+    }
+  }
+
+  /// The colour temperature in mireds.
+  pub fn mireds(&self) -> u16 {
+    (1_000_000 / self.kelvin.max(1) as u32).min(u16::MAX as u32) as u16
+  }
+
+  /// Keeps kelvin within `min..=max`, for devices with a narrower range than usual.
+  pub fn with_kelvin_range(self, min: u16, max: u16) -> Color {
+    Color {
+      kelvin: clamp_kelvin(self.kelvin, min, max),
+      ..self
+    }
+  }
+
+  pub fn raw_hue(&self) -> u16 {
+    self.hue
+  }
+
+  pub fn raw_saturation(&self) -> u16 {
+    self.saturation
+  }
+
+  pub fn raw_brightness(&self) -> u16 {
+    self.brightness
+  }
+
+  /// The colour in linear sRGB, brightness included, each channel in `0.0..=1.0`.
+  pub(crate) fn to_linear_rgb(self) -> (f64, f64, f64) {
+    let (red, green, blue) = self.rgb_f64();
+    (gamma_decode(red), gamma_decode(green), gamma_decode(blue))
+  }
+
+  /// The inverse of `to_linear_rgb`; channels outside `0.0..=1.0` are clipped.
+  pub(crate) fn from_linear_rgb(red: f64, green: f64, blue: f64, kelvin: u16) -> Color {
+    let encode = |channel: f64| gamma_encode(channel.clamp(0.0, 1.0));
+    let (hue, saturation, value) = rgb_to_hsv(encode(red), encode(green), encode(blue));
+    Color {
+      hue: hue_to_raw(hue),
+      saturation: unit_to_raw(saturation),
+      brightness: unit_to_raw(value),
+      kelvin,
+    }
+  }
+
+  fn rgb_f64(&self) -> (f64, f64, f64) {
+    hsv_to_rgb(
+      self.hue as f64 * 360.0 / 65536.0,
+      self.saturation as f64 / 65535.0,
+      self.brightness as f64 / 65535.0,
+    )
+  }
+
   pub fn new(hue: u16, saturation: u16, brightness: u16, kelvin: u16) -> Color {
     Color {
       hue: Color::calc_hue(hue),
@@ -55,21 +249,249 @@ impl Color {
     let saturation = saturation as f32;
     (saturation / 100_f32 * 65535_f32) as u16
   }
-  fn calc_brightness(brightness: u16) -> u16 {
+  pub(crate) fn calc_brightness(brightness: u16) -> u16 {
     let brightness = brightness as f32;
     (brightness / 100_f32 * 65535_f32) as u16
   }
 }
 
+fn hue_to_raw(hue: f64) -> u16 {
+  let hue = hue.rem_euclid(360.0);
+  ((hue / 360.0 * 65536.0).round() as u32 % 65536) as u16
+}
+
+fn unit_to_raw(value: f64) -> u16 {
+  (value.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+fn clamp_kelvin(kelvin: u16, min: u16, max: u16) -> u16 {
+  kelvin.max(min).min(max)
+}
+
+fn rgb_to_hsv(red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+  let max = red.max(green).max(blue);
+  let min = red.min(green).min(blue);
+  let delta = max - min;
+  let saturation = if max > 0.0 { delta / max } else { 0.0 };
+  let hue = if delta <= 0.0 {
+    0.0
+  } else if max == red {
+    60.0 * ((green - blue) / delta)
+  } else if max == green {
+    60.0 * ((blue - red) / delta + 2.0)
+  } else {
+    60.0 * ((red - green) / delta + 4.0)
+  };
+  (hue.rem_euclid(360.0), saturation, max)
+}
+
+fn hsv_to_rgb(hue: f64, saturation: f64, value: f64) -> (f64, f64, f64) {
+  let chroma = value * saturation;
+  let sector = hue / 60.0;
+  let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+  let (red, green, blue) = match sector as u32 {
+    0 => (chroma, x, 0.0),
+    1 => (x, chroma, 0.0),
+    2 => (0.0, chroma, x),
+    3 => (0.0, x, chroma),
+    4 => (x, 0.0, chroma),
+    _ => (chroma, 0.0, x),
+  };
+  let m = value - chroma;
+  (red + m, green + m, blue + m)
+}
+
+fn gamma_encode(linear: f64) -> f64 {
+  if linear <= 0.003_130_8 {
+    12.92 * linear
+  } else {
+    1.055 * linear.powf(1.0 / 2.4) - 0.055
+  }
+}
+
+fn gamma_decode(encoded: f64) -> f64 {
+  if encoded <= 0.040_45 {
+    encoded / 12.92
+  } else {
+    ((encoded + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// Colour names and the hue they give at full saturation.
+const NAMED_HUES: &[(&str, f32)] = &[
+  ("red", 0.0),
+  ("orange", 36.0),
+  ("yellow", 60.0),
+  ("green", 120.0),
+  ("cyan", 180.0),
+  ("blue", 250.0),
+  ("purple", 280.0),
+  ("pink", 325.0),
+];
+
+/// White presets and their kelvin; plain `white` keeps whatever kelvin is set.
+const NAMED_WHITES: &[(&str, Option<u16>)] = &[
+  ("white", None),
+  ("warm white", Some(2700)),
+  ("neutral white", Some(3500)),
+  ("cool white", Some(5000)),
+  ("daylight", Some(6500)),
+];
+
+fn apply_name(color: &mut Color, name: &str) -> bool {
+  if let Some((_, hue)) = NAMED_HUES.iter().find(|(n, _)| *n == name) {
+    color.hue = hue_to_raw(*hue as f64);
+    color.saturation = u16::MAX;
+    return true;
+  }
+  if let Some((_, kelvin)) = NAMED_WHITES.iter().find(|(n, _)| *n == name) {
+    color.saturation = 0;
+    if let Some(kelvin) = kelvin {
+      color.kelvin = *kelvin;
+    }
+    return true;
+  }
+  false
+}
+
+fn parse_number(
+  component: &'static str,
+  value: &str,
+  min: f32,
+  max: f32,
+) -> Result<f32, ParseColorError> {
+  let number = value
+    .parse::<f32>()
+    .map_err(|_| ParseColorError::InvalidNumber {
+      component,
+      value: value.to_owned(),
+    })?;
+  if !(min..=max).contains(&number) {
+    return Err(ParseColorError::OutOfRange {
+      component,
+      value: number as f64,
+      min: min as f64,
+      max: max as f64,
+    });
+  }
+  Ok(number)
+}
+
+fn parse_integer(
+  component: &'static str,
+  value: &str,
+  min: u16,
+  max: u16,
+) -> Result<u16, ParseColorError> {
+  let number = parse_number(component, value, min as f32, max as f32)?;
+  if number.fract() != 0.0 {
+    return Err(ParseColorError::InvalidNumber {
+      component,
+      value: value.to_owned(),
+    });
+  }
+  Ok(number as u16)
+}
+
+fn apply_component(color: &mut Color, component: &str, value: &str) -> Result<(), ParseColorError> {
+  match component {
+    "hue" => color.hue = hue_to_raw(parse_number("hue", value, 0.0, 360.0)? as f64),
+    "saturation" => {
+      color.saturation = unit_to_raw(parse_number("saturation", value, 0.0, 1.0)? as f64)
+    }
+    "brightness" => {
+      color.brightness = unit_to_raw(parse_number("brightness", value, 0.0, 1.0)? as f64)
+    }
+    // a kelvin asks for white, later components can add saturation back
+    "kelvin" => {
+      color.kelvin = parse_integer("kelvin", value, 0, u16::MAX)?;
+      color.saturation = 0;
+    }
+    "hex" => {
+      *color = Color {
+        kelvin: color.kelvin,
+        ..Color::from_hex(value)?
+      }
+    }
+    "rgb" => {
+      let channels = value
+        .split(',')
+        .map(|channel| parse_integer("rgb", channel, 0, 255))
+        .collect::<Result<Vec<_>, _>>()?;
+      if channels.len() != 3 {
+        return Err(ParseColorError::InvalidNumber {
+          component: "rgb",
+          value: value.to_owned(),
+        });
+      }
+      *color = Color {
+        kelvin: color.kelvin,
+        ..Color::from_rgb(channels[0] as u8, channels[1] as u8, channels[2] as u8)
+      };
+    }
+    _ => {
+      return Err(ParseColorError::UnknownToken(format!(
+        "{}:{}",
+        component, value
+      )))
+    }
+  }
+  Ok(())
+}
+
+/// Parses the LIFX HTTP API colour grammar: whitespace separated names
+/// (`red`, `warm white`), `#rrggbb`, and `hue:`, `saturation:`, `brightness:`,
+/// `kelvin:`, `hex:` or `rgb:r,g,b` components, applied left to right.
+/// As in the HTTP API, `kelvin:` also takes saturation down to zero.
+///
+/// Anything left unset is full brightness, no saturation and `DEFAULT_KELVIN`.
+impl FromStr for Color {
+  type Err = ParseColorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let tokens = s
+      .split_whitespace()
+      .map(|token| token.to_lowercase().replace('_', " "))
+      .collect::<Vec<_>>();
+    if tokens.is_empty() {
+      return Err(ParseColorError::Empty);
+    }
+
+    let mut color = Color::from_raw(0, 0, u16::MAX, DEFAULT_KELVIN);
+    let mut i = 0;
+    while i < tokens.len() {
+      let token = &tokens[i];
+      if let Some(pair) = tokens.get(i + 1).map(|next| format!("{} {}", token, next)) {
+        if apply_name(&mut color, &pair) {
+          i += 2;
+          continue;
+        }
+      }
+      if token.starts_with('#') {
+        color = Color {
+          kelvin: color.kelvin,
+          ..Color::from_hex(token)?
+        };
+      } else if let Some((component, value)) = token.split_once(':') {
+        apply_component(&mut color, component, value)?;
+      } else if !apply_name(&mut color, token) {
+        return Err(ParseColorError::UnknownToken(token.clone()));
+      }
+      i += 1;
+    }
+    Ok(color)
+  }
+}
+
+/// Prints every component exactly, in a form `FromStr` reads back to the same colour.
 impl fmt::Display for Color {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (hue, saturation, brightness, kelvin) = self.to_hsbk_f32();
+    // kelvin goes first, as parsing it clears the saturation
     write!(
       f,
-      "{},{},{},{}",
-      self.hue(),
-      self.saturation(),
-      self.brightness(),
-      self.kelvin()
+      "kelvin:{} hue:{} saturation:{} brightness:{}",
+      kelvin, hue, saturation, brightness
     )
   }
 }
@@ -77,6 +499,7 @@ impl fmt::Display for Color {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use proptest::prelude::*;
   #[test]
   fn should_calc_hue() {
     assert_eq!(Color::calc_hue(120), 21845);
@@ -95,4 +518,184 @@ mod tests {
     assert_eq!(color.brightness(), 24);
     assert_eq!(color.kelvin(), 9000);
   }
+  #[test]
+  fn should_round_trip_raw_levels_exactly() {
+    for level in 0..=u16::MAX {
+      let color = Color::from_raw(level, level, level, 3500);
+      assert_eq!(
+        (
+          color.raw_hue(),
+          color.raw_saturation(),
+          color.raw_brightness()
+        ),
+        (level, level, level)
+      );
+
+      let (hue, saturation, brightness, kelvin) = color.to_hsbk_f32();
+      assert_eq!(
+        Color::from_hsbk_f32(hue, saturation, brightness, kelvin),
+        color
+      );
+    }
+  }
+  #[test]
+  fn should_wrap_hue_and_clamp_kelvin() {
+    let color = Color::from_hsbk_f32(480.0, 1.5, -1.0, 20000);
+    assert_eq!(color, Color::from_hsbk_f32(120.0, 1.0, 0.0, MAX_KELVIN));
+    assert_eq!(color.kelvin(), MAX_KELVIN);
+    assert_eq!(
+      Color::from_raw(0, 0, 0, 9000)
+        .with_kelvin_range(2500, 6500)
+        .kelvin(),
+      6500
+    );
+  }
+  #[test]
+  fn should_convert_rgb() {
+    assert_eq!(
+      Color::from_rgb(255, 0, 0),
+      Color::from_raw(0, 65535, 65535, 3500)
+    );
+    assert!((Color::from_rgb(0, 255, 0).to_hsbk_f32().0 - 120.0).abs() < 0.01);
+    assert!((Color::from_rgb(0, 0, 255).to_hsbk_f32().0 - 240.0).abs() < 0.01);
+    for rgb in &[
+      (255, 136, 0),
+      (12, 34, 56),
+      (0, 0, 0),
+      (255, 255, 255),
+      (128, 128, 128),
+    ] {
+      assert_eq!(Color::from_rgb(rgb.0, rgb.1, rgb.2).to_rgb(), *rgb);
+    }
+  }
+  #[test]
+  fn should_convert_hex() {
+    let color = Color::from_hex("#ff8800").unwrap();
+    assert_eq!(color.to_rgb(), (255, 136, 0));
+    assert_eq!(color.to_hex(), "#ff8800");
+    assert_eq!(Color::from_hex("FF8800").unwrap(), color);
+    assert!(Color::from_hex("#ff88").is_err());
+    assert!(Color::from_hex("#gg8800").is_err());
+  }
+  #[test]
+  fn should_convert_xy() {
+    let (x, y) = Color::from_rgb(255, 255, 255).to_xy();
+    assert!((x - 0.3127).abs() < 0.001 && (y - 0.3290).abs() < 0.001);
+
+    let red = Color::from_rgb(255, 0, 0);
+    let (x, y) = red.to_xy();
+    assert!((x - 0.64).abs() < 0.001 && (y - 0.33).abs() < 0.001);
+    let color = Color::from_xy(x, y, 1.0);
+    assert_eq!(color.to_rgb(), (255, 0, 0));
+  }
+  #[test]
+  fn should_convert_mireds() {
+    let color = Color::from_mireds(370, 0.5);
+    assert_eq!(color.kelvin(), 2702);
+    assert_eq!(color.mireds(), 370);
+    assert_eq!(color.saturation(), 0);
+    assert_eq!(Color::from_mireds(1000, 1.0).kelvin(), MIN_KELVIN);
+  }
+  #[test]
+  fn should_parse_names() {
+    let red: Color = "red".parse().unwrap();
+    assert_eq!(red, Color::from_raw(0, 65535, 65535, DEFAULT_KELVIN));
+
+    let warm: Color = "warm white".parse().unwrap();
+    assert_eq!(warm, Color::from_raw(0, 0, 65535, 2700));
+    assert_eq!("Warm_White".parse::<Color>().unwrap(), warm);
+    assert_eq!(
+      "blue brightness:0.5".parse::<Color>().unwrap(),
+      Color::from_hsbk_f32(250.0, 1.0, 0.5, DEFAULT_KELVIN)
+    );
+  }
+  #[test]
+  fn should_parse_components() {
+    assert_eq!(
+      "hue:120 saturation:0.5".parse::<Color>().unwrap(),
+      Color::from_hsbk_f32(120.0, 0.5, 1.0, DEFAULT_KELVIN)
+    );
+    assert_eq!(
+      "kelvin:2700".parse::<Color>().unwrap(),
+      Color::from_raw(0, 0, 65535, 2700)
+    );
+    assert_eq!(
+      "#ff8800".parse::<Color>().unwrap(),
+      Color::from_hex("#ff8800").unwrap()
+    );
+    assert_eq!(
+      "red kelvin:2700".parse::<Color>().unwrap(),
+      Color::from_raw(0, 0, 65535, 2700)
+    );
+    assert_eq!(
+      "red kelvin:2700 saturation:0.5".parse::<Color>().unwrap(),
+      Color::from_hsbk_f32(0.0, 0.5, 1.0, 2700)
+    );
+    assert_eq!(
+      "kelvin:4000 rgb:255,136,0".parse::<Color>().unwrap(),
+      Color {
+        kelvin: 4000,
+        ..Color::from_rgb(255, 136, 0)
+      }
+    );
+  }
+  #[test]
+  fn should_report_parse_errors() {
+    assert_eq!("  ".parse::<Color>(), Err(ParseColorError::Empty));
+    assert_eq!(
+      "red mauve".parse::<Color>(),
+      Err(ParseColorError::UnknownToken("mauve".to_owned()))
+    );
+    assert_eq!(
+      "hue:abc".parse::<Color>(),
+      Err(ParseColorError::InvalidNumber {
+        component: "hue",
+        value: "abc".to_owned()
+      })
+    );
+    assert_eq!(
+      "saturation:1.5".parse::<Color>(),
+      Err(ParseColorError::OutOfRange {
+        component: "saturation",
+        value: 1.5,
+        min: 0.0,
+        max: 1.0
+      })
+    );
+    assert_eq!(
+      "kelvin:2700.5".parse::<Color>(),
+      Err(ParseColorError::InvalidNumber {
+        component: "kelvin",
+        value: "2700.5".to_owned()
+      })
+    );
+    assert_eq!(
+      "rgb:1,2".parse::<Color>(),
+      Err(ParseColorError::InvalidNumber {
+        component: "rgb",
+        value: "1,2".to_owned()
+      })
+    );
+    assert_eq!(
+      "#ff88".parse::<Color>(),
+      Err(ParseColorError::InvalidHex("#ff88".to_owned()))
+    );
+    assert_eq!(
+      "tint:3".parse::<Color>(),
+      Err(ParseColorError::UnknownToken("tint:3".to_owned()))
+    );
+  }
+
+  proptest! {
+    #[test]
+    fn should_round_trip_display(
+      hue: u16,
+      saturation: u16,
+      brightness: u16,
+      kelvin: u16,
+    ) {
+      let color = Color::from_raw(hue, saturation, brightness, kelvin);
+      prop_assert_eq!(color.to_string().parse::<Color>(), Ok(color));
+    }
+  }
 }
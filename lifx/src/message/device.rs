@@ -1,50 +1,101 @@
+use super::label::Label;
+use super::lifx_uuid::LifxUuid;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct StateServicePayload {
   pub service: u8,
   pub port: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StateHostInfoPayload {
   pub signal: f32,
   pub tx: u32,
   pub rx: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct FirmwarePayload {
   pub build: u64,
   pub version_minor: u16,
   pub version_major: u16,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StateWifiInfoPayload {
   pub signal: f32,
   pub tx: u32,
   pub rx: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StateVersionPayload {
   pub vendor: u32,
   pub product: u32,
   pub version: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StateInfoPayload {
   pub time: u64,
   pub uptime: u64,
   pub downtime: u64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelPayload {
+  pub label: Label,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct LocationPayload {
-  pub location: [u8; 16],
-  pub label: [u8; 32],
+  pub location: LifxUuid,
+  pub label: Label,
   pub updated_at: u64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct GroupPayload {
-  pub group: [u8; 16],
-  pub label: [u8; 32],
+  pub group: LifxUuid,
+  pub label: Label,
   pub updated_at: u64, // docs say i64??
 }
 
+impl LabelPayload {
+  pub fn new(label: Label) -> Self {
+    Self { label }
+  }
+}
+
+impl LocationPayload {
+  pub fn new(location: LifxUuid, label: Label) -> Self {
+    Self {
+      location,
+      label,
+      updated_at: now(),
+    }
+  }
+}
+
+impl GroupPayload {
+  pub fn new(group: LifxUuid, label: Label) -> Self {
+    Self {
+      group,
+      label,
+      updated_at: now(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct EchoPayload {
   pub payload: [u8; 64],
 }
+
+/// Devices keep whichever location or group was updated last, so stamp changes with the current time in nanoseconds.
+fn now() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
@@ -0,0 +1,72 @@
+use super::color::Color;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// The most colours a tile effect palette can hold.
+pub const PALETTE_COLORS: usize = 16;
+
+#[repr(u32)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+pub enum MoveDirection {
+  Right = 0,
+  Left = 1,
+}
+
+/// A firmware effect running across the zones of a multizone device.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum MultiZoneEffect {
+  Off,
+  Move(MoveDirection),
+}
+
+/// Used for both `SetMultiZoneEffect` and `StateMultiZoneEffect`, which share a layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiZoneEffectPayload {
+  pub instance_id: u32,
+  pub effect: MultiZoneEffect,
+  /// Milliseconds per cycle.
+  pub speed: u32,
+  /// Nanoseconds to run for, zero runs until stopped.
+  pub duration: u64,
+}
+
+#[repr(u8)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+pub enum SkyType {
+  Sunrise = 0,
+  Sunset = 1,
+  Clouds = 2,
+}
+
+/// A firmware effect running on a matrix device.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum TileEffect {
+  Off,
+  Morph,
+  Flame,
+  Sky {
+    sky_type: SkyType,
+    cloud_saturation_min: u8,
+    cloud_saturation_max: u8,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileEffectPayload {
+  pub instance_id: u32,
+  pub effect: TileEffect,
+  /// Milliseconds per cycle.
+  pub speed: u32,
+  /// Nanoseconds to run for, zero runs until stopped.
+  pub duration: u64,
+  /// At most `PALETTE_COLORS` colours for the effect to use.
+  pub palette: Vec<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetTileEffectPayload {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetTileEffectPayload(pub TileEffectPayload);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateTileEffectPayload(pub TileEffectPayload);
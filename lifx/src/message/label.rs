@@ -0,0 +1,104 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// A device, group or location name, as carried in a 32 byte NUL padded field.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Label(String);
+
+impl Label {
+  pub const SIZE: usize = 32;
+
+  /// Truncates `label` to fit, stopping early at a NUL since that ends the label on the wire.
+  pub fn new(label: &str) -> Self {
+    let label = label.split('\0').next().unwrap_or_default();
+    let mut end = label.len().min(Self::SIZE);
+    while !label.is_char_boundary(end) {
+      end -= 1;
+    }
+    Self(label[..end].to_string())
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+    let mut bytes = [0_u8; Self::SIZE];
+    bytes[..self.0.len()].copy_from_slice(self.0.as_bytes());
+    bytes
+  }
+}
+
+/// Bytes that aren't valid UTF-8 become U+FFFD, so one badly named device
+/// doesn't stop the rest of its state from being read.
+impl From<[u8; Label::SIZE]> for Label {
+  fn from(bytes: [u8; Label::SIZE]) -> Self {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(Self::SIZE);
+    Self::new(&String::from_utf8_lossy(&bytes[..end]))
+  }
+}
+
+impl From<&str> for Label {
+  fn from(label: &str) -> Self {
+    Self::new(label)
+  }
+}
+
+impl From<String> for Label {
+  fn from(label: String) -> Self {
+    Self::new(&label)
+  }
+}
+
+impl FromStr for Label {
+  type Err = Infallible;
+
+  fn from_str(label: &str) -> Result<Self, Self::Err> {
+    Ok(Self::new(label))
+  }
+}
+
+impl fmt::Display for Label {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(&self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_pad_with_nuls() {
+    let bytes = Label::new("Kitchen").to_bytes();
+    assert_eq!(&bytes[..8], b"Kitchen\0");
+    assert_eq!(Label::from(bytes).as_str(), "Kitchen");
+  }
+  #[test]
+  fn should_truncate_on_char_boundary() {
+    // 31 ascii bytes followed by a 2 byte character that doesn't fit
+    let label = format!("{}é", "a".repeat(31));
+    assert_eq!(Label::new(&label).as_str(), "a".repeat(31));
+    assert_eq!(Label::new(&"a".repeat(40)).as_str().len(), 32);
+  }
+  #[test]
+  fn should_stop_at_nul() {
+    assert_eq!(Label::new("Den\0ignored").as_str(), "Den");
+  }
+  #[test]
+  fn should_replace_invalid_utf8() {
+    let mut bytes = Label::new("Den").to_bytes();
+    bytes[1] = 0xff;
+    assert_eq!(Label::from(bytes).as_str(), "D\u{fffd}n");
+
+    // replacements are wider than the bytes they stand for, so the label is cut to fit
+    let label = Label::from([0xff; 32]);
+    assert_eq!(label.as_str(), "\u{fffd}".repeat(10));
+    assert_eq!(Label::from(label.to_bytes()), label);
+  }
+  #[test]
+  fn should_parse_and_display() {
+    let label: Label = "Living Room".parse().unwrap();
+    assert_eq!(label.to_string(), "Living Room");
+  }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// The 16 byte id of a group or location, kept in the order it is sent on the wire.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LifxUuid([u8; 16]);
+
+impl LifxUuid {
+  pub fn from_bytes(bytes: [u8; 16]) -> Self {
+    Self(bytes)
+  }
+
+  pub fn as_bytes(&self) -> &[u8; 16] {
+    &self.0
+  }
+}
+
+impl From<Uuid> for LifxUuid {
+  fn from(uuid: Uuid) -> Self {
+    Self(*uuid.as_bytes())
+  }
+}
+
+impl From<LifxUuid> for Uuid {
+  fn from(uuid: LifxUuid) -> Self {
+    Uuid::from_bytes(uuid.0)
+  }
+}
+
+impl FromStr for LifxUuid {
+  type Err = anyhow::Error;
+
+  fn from_str(uuid: &str) -> Result<Self, Self::Err> {
+    let uuid = Uuid::parse_str(uuid)
+      .map_err(|err| anyhow::Error::msg(format!("invalid uuid {}: {}", uuid, err)))?;
+    Ok(uuid.into())
+  }
+}
+
+impl fmt::Display for LifxUuid {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", Uuid::from_bytes(self.0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_round_trip_through_string() {
+    let uuid: LifxUuid = "1c8de82b-8e2d-4d6c-9a3f-1b1c0e2f3a4b".parse().unwrap();
+    assert_eq!(uuid.as_bytes()[0], 0x1c);
+    assert_eq!(uuid.to_string(), "1c8de82b-8e2d-4d6c-9a3f-1b1c0e2f3a4b");
+  }
+  #[test]
+  fn should_reject_garbage() {
+    assert!("not a uuid".parse::<LifxUuid>().is_err());
+  }
+}
@@ -1,25 +1,58 @@
 use super::color::Color;
+use super::label::Label;
 use crate::proto::Power;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SetColorPayload {
-  pub(crate) color: Color,
-  pub(crate) duration: u32,
+  pub color: Color,
+  pub duration: u32,
+}
+
+#[repr(u8)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+pub enum Waveform {
+  Saw = 0,
+  Sine = 1,
+  HalfSine = 2,
+  Triangle = 3,
+  Pulse = 4,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SetWaveformPayload {
-  // reserve u8
-  pub(crate) transient: i8, // maybe this is a u8?
-  pub(crate) color: Color,
-  pub(crate) period: u32,
-  pub(crate) cycles: f32,
-  pub(crate) skew_ration: i16,
-  pub(crate) waveform: u8,
+  /// Return to the original colour once the cycles finish.
+  pub transient: bool,
+  pub color: Color,
+  /// Length of one cycle in milliseconds.
+  pub period: u32,
+  pub cycles: f32,
+  /// For `Pulse`, how much of each cycle is spent on the original colour,
+  /// from -32768 (none) to 32767 (all of it).
+  pub skew_ratio: i16,
+  pub waveform: Waveform,
+}
+
+/// A waveform that only changes the channels whose flag is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetWaveformOptionalPayload {
+  pub transient: bool,
+  pub color: Color,
+  pub period: u32,
+  pub cycles: f32,
+  pub skew_ratio: i16,
+  pub waveform: Waveform,
+  pub set_hue: bool,
+  pub set_saturation: bool,
+  pub set_brightness: bool,
+  pub set_kelvin: bool,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StatePayload {
   pub color: Color,
   pub power: u16,
-  pub label: [u8; 32],
+  pub label: Label,
 }
 
 impl StatePayload {
@@ -29,16 +62,80 @@ impl StatePayload {
   }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct SetPowerPayload {
-  pub(crate) level: Power,
-  pub(crate) duration: u32,
+  pub level: Power,
+  pub duration: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct StatePowerPayload {
   pub level: Power,
 }
 
+/// Brightness of the infrared channel on LIFX+ bulbs.
+#[derive(Debug, Clone, PartialEq)]
 pub struct InfraredPayload {
-  // this is the brightness playa
   pub(crate) brightness: u16,
 }
+
+impl InfraredPayload {
+  /// `brightness` is a percentage, the same as `Color`'s brightness.
+  pub fn new(brightness: u16) -> Self {
+    Self {
+      brightness: Color::calc_brightness(brightness),
+    }
+  }
+  pub fn set_brightness(&mut self, brightness: u16) {
+    self.brightness = Color::calc_brightness(brightness);
+  }
+  pub fn brightness(&self) -> u16 {
+    let brightness = self.brightness as f32;
+    (brightness * 100_f32 / 65535_f32).ceil() as u16
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetHevCyclePayload {
+  pub enable: bool,
+  /// Zero runs the cycle for the configured default duration.
+  pub duration_s: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateHevCyclePayload {
+  pub duration_s: u32,
+  pub remaining_s: u32,
+  /// Whether the light was on before the cycle started.
+  pub last_power: bool,
+}
+
+impl StateHevCyclePayload {
+  pub fn is_running(&self) -> bool {
+    self.remaining_s > 0
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HevCycleConfigurationPayload {
+  /// Flash briefly when a cycle finishes.
+  pub indication: bool,
+  pub duration_s: u32,
+}
+
+#[repr(u8)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+pub enum LastHevCycleResult {
+  Success = 0,
+  Busy = 1,
+  InterruptedByReset = 2,
+  InterruptedByHomekit = 3,
+  InterruptedByLan = 4,
+  InterruptedByCloud = 5,
+  None = 255,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateLastHevCycleResultPayload {
+  pub result: LastHevCycleResult,
+}
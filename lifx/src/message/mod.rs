@@ -1,13 +1,26 @@
 mod color;
 mod device;
+mod effect;
+mod label;
+mod lifx_uuid;
 mod light;
+mod multizone;
+mod relay;
+mod tile;
 
 pub use color::*;
 pub use device::*;
+pub use effect::*;
+pub use label::*;
+pub use lifx_uuid::*;
 pub use light::*;
+pub use multizone::*;
+pub use relay::*;
+pub use tile::*;
 
-use crate::proto::Serializable;
+use crate::proto::{Deserializable, Serializable};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct EmptyPayload {}
 
 impl Serializable for EmptyPayload {
@@ -15,3 +28,9 @@ impl Serializable for EmptyPayload {
     Ok(())
   }
 }
+
+impl Deserializable for EmptyPayload {
+  fn deserialize(_: &mut bytes::Bytes) -> anyhow::Result<Self> {
+    Ok(Self {})
+  }
+}
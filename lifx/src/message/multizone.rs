@@ -0,0 +1,64 @@
+use super::color::Color;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// The most zones a single extended multizone message can carry.
+pub const EXTENDED_ZONES: usize = 82;
+
+/// The most zones a single `StateMultiZone` message can carry.
+pub const MULTI_ZONES: usize = 8;
+
+#[repr(u8)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+pub enum ApplicationRequest {
+  /// Buffer the change until a later message applies it.
+  NoApply = 0,
+  /// Apply this change along with anything buffered.
+  Apply = 1,
+  /// Ignore the colour and apply whatever is buffered.
+  ApplyOnly = 2,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetColorZonesPayload {
+  pub start_index: u8,
+  pub end_index: u8,
+  pub color: Color,
+  pub duration: u32,
+  pub apply: ApplicationRequest,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetColorZonesPayload {
+  pub start_index: u8,
+  pub end_index: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateZonePayload {
+  pub zones_count: u8,
+  pub zone_index: u8,
+  pub color: Color,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateMultiZonePayload {
+  pub zones_count: u8,
+  pub zone_index: u8,
+  pub colors: [Color; MULTI_ZONES],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetExtendedColorZonesPayload {
+  pub duration: u32,
+  pub apply: ApplicationRequest,
+  pub zone_index: u16,
+  /// At most `EXTENDED_ZONES` colours, starting at `zone_index`.
+  pub colors: Vec<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateExtendedColorZonesPayload {
+  pub zones_count: u16,
+  pub zone_index: u16,
+  pub colors: Vec<Color>,
+}
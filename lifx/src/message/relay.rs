@@ -0,0 +1,18 @@
+use crate::proto::Power;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetRPowerPayload {
+  pub relay_index: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetRPowerPayload {
+  pub relay_index: u8,
+  pub level: Power,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateRPowerPayload {
+  pub relay_index: u8,
+  pub level: Power,
+}
@@ -0,0 +1,68 @@
+use super::color::Color;
+
+/// The most colours a single `Set64` or `State64` message can carry.
+pub const TILE_COLORS: usize = 64;
+
+/// The most tiles a `StateDeviceChain` message can describe.
+pub const TILE_DEVICES: usize = 16;
+
+/// One tile in a chain, as reported by `StateDeviceChain`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tile {
+  pub accel_meas_x: i16,
+  pub accel_meas_y: i16,
+  pub accel_meas_z: i16,
+  pub user_x: f32,
+  pub user_y: f32,
+  pub width: u8,
+  pub height: u8,
+  pub device_version_vendor: u32,
+  pub device_version_product: u32,
+  pub firmware_build: u64,
+  pub firmware_version_minor: u16,
+  pub firmware_version_major: u16,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDeviceChainPayload {
+  pub start_index: u8,
+  /// At most `TILE_DEVICES` tiles, starting at `start_index`.
+  pub tile_devices: Vec<Tile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetUserPositionPayload {
+  pub tile_index: u8,
+  pub user_x: f32,
+  pub user_y: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Get64Payload {
+  pub tile_index: u8,
+  pub length: u8,
+  pub x: u8,
+  pub y: u8,
+  pub width: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct State64Payload {
+  pub tile_index: u8,
+  pub x: u8,
+  pub y: u8,
+  pub width: u8,
+  pub colors: Vec<Color>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Set64Payload {
+  pub tile_index: u8,
+  pub length: u8,
+  pub x: u8,
+  pub y: u8,
+  pub width: u8,
+  pub duration: u32,
+  /// At most `TILE_COLORS` colours, filled in rows of `width`.
+  pub colors: Vec<Color>,
+}
@@ -0,0 +1,172 @@
+mod products;
+
+use crate::message::{Color, FirmwarePayload, StateVersionPayload};
+use products::PRODUCTS;
+
+/// What a device can do. Lights without colour only take kelvin and brightness.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Features {
+  pub color: bool,
+  pub infrared: bool,
+  pub multizone: bool,
+  /// Whether the device understands the extended zone messages, which move 82 zones at a time.
+  pub extended_multizone: bool,
+  pub matrix: bool,
+  pub chain: bool,
+  pub hev: bool,
+  pub relays: bool,
+  pub buttons: bool,
+  /// The kelvin the device supports, `None` for devices without a light.
+  pub temperature_range: Option<(u16, u16)>,
+}
+
+/// Features a product gains from a firmware version onwards.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Upgrade {
+  pub major: u16,
+  pub minor: u16,
+  pub extended_multizone: Option<bool>,
+  pub temperature_range: Option<(u16, u16)>,
+}
+
+/// An entry of the product registry.
+#[derive(Debug, PartialEq)]
+pub struct Product {
+  pub vendor: u32,
+  pub product: u32,
+  pub name: &'static str,
+  pub features: Features,
+  pub upgrades: &'static [Upgrade],
+}
+
+impl Product {
+  pub fn lookup(vendor: u32, product: u32) -> Option<&'static Product> {
+    PRODUCTS
+      .iter()
+      .find(|entry| entry.vendor == vendor && entry.product == product)
+  }
+
+  /// Every product in the registry.
+  pub fn all() -> &'static [Product] {
+    PRODUCTS
+  }
+
+  /// The product's features once the upgrades up to firmware `major.minor` are applied.
+  pub fn features_at(&self, major: u16, minor: u16) -> Features {
+    let mut features = self.features;
+    for upgrade in self.upgrades {
+      if (major, minor) < (upgrade.major, upgrade.minor) {
+        continue;
+      }
+      if let Some(extended_multizone) = upgrade.extended_multizone {
+        features.extended_multizone = extended_multizone;
+      }
+      if let Some(temperature_range) = upgrade.temperature_range {
+        features.temperature_range = Some(temperature_range);
+      }
+    }
+    features
+  }
+}
+
+/// What one device can do, given its product and the firmware it runs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Capabilities {
+  pub product: &'static Product,
+  pub firmware: (u16, u16),
+  pub features: Features,
+}
+
+impl Capabilities {
+  /// `None` when the product isn't in the registry.
+  pub fn new(version: &StateVersionPayload, firmware: &FirmwarePayload) -> Option<Capabilities> {
+    let product = Product::lookup(version.vendor, version.product)?;
+    let features = product.features_at(firmware.version_major, firmware.version_minor);
+    Some(Capabilities {
+      product,
+      firmware: (firmware.version_major, firmware.version_minor),
+      features,
+    })
+  }
+
+  pub fn name(&self) -> &'static str {
+    self.product.name
+  }
+
+  /// The closest colour the device can show: white-only lights lose hue and
+  /// saturation, and kelvin is kept within the device's range.
+  pub fn restrict(&self, color: Color) -> Color {
+    let mut color = color;
+    if !self.features.color {
+      color = Color::from_raw(0, 0, color.raw_brightness(), color.kelvin());
+    }
+    if let Some((min, max)) = self.features.temperature_range {
+      color = color.with_kelvin_range(min, max);
+    }
+    color
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  fn capabilities(product: u32, major: u16, minor: u16) -> Option<Capabilities> {
+    let version = StateVersionPayload {
+      vendor: 1,
+      product,
+      version: 0,
+    };
+    let firmware = FirmwarePayload {
+      build: 0,
+      version_minor: minor,
+      version_major: major,
+    };
+    Capabilities::new(&version, &firmware)
+  }
+  #[test]
+  fn should_lookup_products() {
+    let beam = Product::lookup(1, 38).unwrap();
+    assert_eq!(beam.name, "LIFX Beam");
+    assert!(beam.features.multizone);
+    assert!(Product::lookup(1, 9999).is_none());
+    assert!(Product::lookup(2, 38).is_none());
+  }
+  #[test]
+  fn should_apply_firmware_upgrades() {
+    let old = capabilities(32, 2, 76).unwrap();
+    assert!(old.features.multizone);
+    assert!(!old.features.extended_multizone);
+    assert_eq!(old.features.temperature_range, Some((2500, 9000)));
+
+    let new = capabilities(32, 2, 80).unwrap();
+    assert!(new.features.extended_multizone);
+    assert_eq!(new.features.temperature_range, Some((1500, 9000)));
+
+    assert!(capabilities(32, 3, 0).unwrap().features.extended_multizone);
+    assert!(capabilities(9999, 3, 0).is_none());
+  }
+  #[test]
+  fn should_restrict_colors() {
+    let white_to_warm = capabilities(81, 3, 70).unwrap();
+    let color = Color::from_raw(21845, 65535, 32768, 9000);
+    assert_eq!(
+      white_to_warm.restrict(color),
+      Color::from_raw(0, 0, 32768, 6500)
+    );
+
+    let a19 = capabilities(27, 2, 0).unwrap();
+    assert_eq!(
+      a19.restrict(Color::from_raw(21845, 65535, 32768, 1500)),
+      Color::from_raw(21845, 65535, 32768, 2500)
+    );
+  }
+  #[test]
+  fn should_list_unique_products() {
+    let products = Product::all();
+    for (i, product) in products.iter().enumerate() {
+      assert!(!products[..i]
+        .iter()
+        .any(|other| (other.vendor, other.product) == (product.vendor, product.product)));
+    }
+  }
+}
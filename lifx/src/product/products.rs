@@ -0,0 +1,1133 @@
+// Generated by lifx/scripts/products.py from LIFX's products.json, don't edit by hand.
+use super::{Features, Product, Upgrade};
+
+/// Features of LIFX products unless listed otherwise.
+const VENDOR_1: Features = Features {
+  color: false,
+  infrared: false,
+  multizone: false,
+  extended_multizone: false,
+  matrix: false,
+  chain: false,
+  hev: false,
+  relays: false,
+  buttons: false,
+  temperature_range: None,
+};
+
+pub(super) const PRODUCTS: &[Product] = &[
+  Product {
+    vendor: 1,
+    product: 1,
+    name: "LIFX Original 1000",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 3,
+    name: "LIFX Color 650",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 10,
+    name: "LIFX White 800 (Low Voltage)",
+    features: Features {
+      temperature_range: Some((2700, 6500)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 11,
+    name: "LIFX White 800 (High Voltage)",
+    features: Features {
+      temperature_range: Some((2700, 6500)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 15,
+    name: "LIFX Color 1000",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 18,
+    name: "LIFX White 900 BR30 (Low Voltage)",
+    features: Features {
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 19,
+    name: "LIFX White 900 BR30 (High Voltage)",
+    features: Features {
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 20,
+    name: "LIFX Color 1000 BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 22,
+    name: "LIFX Color 1000",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 27,
+    name: "LIFX A19",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 28,
+    name: "LIFX BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 29,
+    name: "LIFX A19 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 30,
+    name: "LIFX BR30 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 31,
+    name: "LIFX Z",
+    features: Features {
+      color: true,
+      multizone: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[
+      Upgrade {
+        major: 2,
+        minor: 60,
+        extended_multizone: Some(true),
+        temperature_range: None,
+      },
+      Upgrade {
+        major: 2,
+        minor: 80,
+        extended_multizone: None,
+        temperature_range: Some((1500, 9000)),
+      },
+    ],
+  },
+  Product {
+    vendor: 1,
+    product: 32,
+    name: "LIFX Z",
+    features: Features {
+      color: true,
+      multizone: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[
+      Upgrade {
+        major: 2,
+        minor: 77,
+        extended_multizone: Some(true),
+        temperature_range: None,
+      },
+      Upgrade {
+        major: 2,
+        minor: 80,
+        extended_multizone: None,
+        temperature_range: Some((1500, 9000)),
+      },
+    ],
+  },
+  Product {
+    vendor: 1,
+    product: 36,
+    name: "LIFX Downlight",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 37,
+    name: "LIFX Downlight",
+    features: Features {
+      color: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 2,
+      minor: 80,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 38,
+    name: "LIFX Beam",
+    features: Features {
+      color: true,
+      multizone: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[
+      Upgrade {
+        major: 2,
+        minor: 77,
+        extended_multizone: Some(true),
+        temperature_range: None,
+      },
+      Upgrade {
+        major: 2,
+        minor: 80,
+        extended_multizone: None,
+        temperature_range: Some((1500, 9000)),
+      },
+    ],
+  },
+  Product {
+    vendor: 1,
+    product: 39,
+    name: "LIFX Downlight White to Warm",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 40,
+    name: "LIFX Downlight",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 43,
+    name: "LIFX A19",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 44,
+    name: "LIFX BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 45,
+    name: "LIFX A19 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 46,
+    name: "LIFX BR30 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 49,
+    name: "LIFX Mini Color",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 50,
+    name: "LIFX Mini White to Warm",
+    features: Features {
+      temperature_range: Some((1500, 4000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 51,
+    name: "LIFX Mini White",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 52,
+    name: "LIFX GU10",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 53,
+    name: "LIFX GU10",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 55,
+    name: "LIFX Tile",
+    features: Features {
+      color: true,
+      matrix: true,
+      chain: true,
+      temperature_range: Some((2500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[Upgrade {
+      major: 3,
+      minor: 50,
+      extended_multizone: None,
+      temperature_range: Some((1500, 9000)),
+    }],
+  },
+  Product {
+    vendor: 1,
+    product: 57,
+    name: "LIFX Candle",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 59,
+    name: "LIFX Mini Color",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 60,
+    name: "LIFX Mini White to Warm",
+    features: Features {
+      temperature_range: Some((1500, 4000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 61,
+    name: "LIFX Mini White",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 62,
+    name: "LIFX A19",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 63,
+    name: "LIFX BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 64,
+    name: "LIFX A19 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 65,
+    name: "LIFX BR30 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 66,
+    name: "LIFX Mini White",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 68,
+    name: "LIFX Candle",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 70,
+    name: "LIFX Switch",
+    features: Features {
+      relays: true,
+      buttons: true,
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 71,
+    name: "LIFX Switch",
+    features: Features {
+      relays: true,
+      buttons: true,
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 81,
+    name: "LIFX Candle White to Warm",
+    features: Features {
+      temperature_range: Some((2200, 6500)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 82,
+    name: "LIFX Filament Clear",
+    features: Features {
+      temperature_range: Some((2100, 2100)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 85,
+    name: "LIFX Filament Amber",
+    features: Features {
+      temperature_range: Some((2000, 2000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 87,
+    name: "LIFX Mini White",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 88,
+    name: "LIFX Mini White",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 89,
+    name: "LIFX Switch",
+    features: Features {
+      relays: true,
+      buttons: true,
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 90,
+    name: "LIFX Clean",
+    features: Features {
+      color: true,
+      hev: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 91,
+    name: "LIFX Color",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 92,
+    name: "LIFX Color",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 93,
+    name: "LIFX A19 US",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 94,
+    name: "LIFX BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 96,
+    name: "LIFX Candle White to Warm",
+    features: Features {
+      temperature_range: Some((2200, 6500)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 97,
+    name: "LIFX A19",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 98,
+    name: "LIFX BR30",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 99,
+    name: "LIFX Clean",
+    features: Features {
+      color: true,
+      hev: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 100,
+    name: "LIFX Filament Clear",
+    features: Features {
+      temperature_range: Some((2100, 2100)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 101,
+    name: "LIFX Filament Amber",
+    features: Features {
+      temperature_range: Some((2000, 2000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 109,
+    name: "LIFX A19 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 110,
+    name: "LIFX BR30 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 111,
+    name: "LIFX A19 Night Vision",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 112,
+    name: "LIFX BR30 Night Vision Intl",
+    features: Features {
+      color: true,
+      infrared: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 113,
+    name: "LIFX Mini WW US",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 114,
+    name: "LIFX Mini WW Intl",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 115,
+    name: "LIFX Switch",
+    features: Features {
+      relays: true,
+      buttons: true,
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 116,
+    name: "LIFX Switch",
+    features: Features {
+      relays: true,
+      buttons: true,
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 117,
+    name: "LIFX Z US",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 118,
+    name: "LIFX Z Intl",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 119,
+    name: "LIFX Beam US",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 120,
+    name: "LIFX Beam Intl",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 123,
+    name: "LIFX Color US",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 124,
+    name: "LIFX Color Intl",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 125,
+    name: "LIFX White to Warm US",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 126,
+    name: "LIFX White to Warm Intl",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 127,
+    name: "LIFX White US",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 128,
+    name: "LIFX White Intl",
+    features: Features {
+      temperature_range: Some((2700, 2700)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 129,
+    name: "LIFX Color US",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 130,
+    name: "LIFX Color Intl",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 131,
+    name: "LIFX White To Warm US",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 132,
+    name: "LIFX White To Warm Intl",
+    features: Features {
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 135,
+    name: "LIFX GU10 Color US",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 136,
+    name: "LIFX GU10 Color Intl",
+    features: Features {
+      color: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 137,
+    name: "LIFX Candle Color US",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 138,
+    name: "LIFX Candle Color Intl",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 141,
+    name: "LIFX Neon US",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 142,
+    name: "LIFX Neon Intl",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 143,
+    name: "LIFX String US",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 144,
+    name: "LIFX String Intl",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 161,
+    name: "LIFX Outdoor Neon US",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 162,
+    name: "LIFX Outdoor Neon Intl",
+    features: Features {
+      color: true,
+      multizone: true,
+      extended_multizone: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 176,
+    name: "LIFX Ceiling US",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+  Product {
+    vendor: 1,
+    product: 177,
+    name: "LIFX Ceiling Intl",
+    features: Features {
+      color: true,
+      matrix: true,
+      temperature_range: Some((1500, 9000)),
+      ..VENDOR_1
+    },
+    upgrades: &[],
+  },
+];
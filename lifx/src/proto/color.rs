@@ -1,7 +1,16 @@
 use crate::message::Color;
+use crate::proto::serialize::ensure_remaining;
 use crate::proto::{Deserializable, Serializable};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
+/// Pads the unused slots of fixed-size colour buffers.
+pub(crate) const BLANK: Color = Color {
+  hue: 0,
+  saturation: 0,
+  brightness: 0,
+  kelvin: 0,
+};
+
 impl Serializable for Color {
   fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
     bytes.put_u16_le(self.hue);
@@ -14,6 +23,7 @@ impl Serializable for Color {
 
 impl Deserializable for Color {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 8)?;
     let hue = bytes.get_u16_le();
     let saturation = bytes.get_u16_le();
     let brightness = bytes.get_u16_le();
@@ -0,0 +1,122 @@
+use super::message::{Message, MessageType};
+use super::packet::IncomingPacket;
+use super::serialize::Deserializable;
+use bytes::Bytes;
+use std::any::Any;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+type Decoder = fn(&mut Bytes) -> anyhow::Result<Box<dyn Any + Send + Sync>>;
+
+/// Decodes packets, including message types this crate doesn't know about.
+///
+/// Known message types always decode to their own `Message` variant. Types
+/// registered here decode to `Message::Custom`, anything else to `Message::Unknown`.
+#[derive(Clone, Default)]
+pub struct Decoders {
+  custom: HashMap<u16, Decoder>,
+}
+
+impl Decoders {
+  /// Decodes payloads of `message_type` as `T`.
+  pub fn register<T>(&mut self, message_type: u16) -> anyhow::Result<()>
+  where
+    T: Deserializable + Send + Sync + 'static,
+  {
+    if let Ok(known) = MessageType::try_from(message_type) {
+      return Err(anyhow::Error::msg(format!(
+        "message type {} is already decoded as {}",
+        message_type, known
+      )));
+    }
+    self.custom.insert(message_type, decode_custom::<T>);
+    Ok(())
+  }
+
+  pub fn decode(&self, packet: &IncomingPacket) -> anyhow::Result<Message> {
+    let mut payload = packet.payload();
+    if let Ok(message_type) = packet.message_type() {
+      return Message::decode(message_type, &mut payload);
+    }
+
+    let message_type = packet.raw_message_type();
+    match self.custom.get(&message_type) {
+      Some(decode) => Ok(Message::Custom {
+        message_type,
+        payload: decode(&mut payload)?,
+      }),
+      None => Ok(Message::Unknown {
+        message_type,
+        payload,
+      }),
+    }
+  }
+}
+
+fn decode_custom<T>(bytes: &mut Bytes) -> anyhow::Result<Box<dyn Any + Send + Sync>>
+where
+  T: Deserializable + Send + Sync + 'static,
+{
+  Ok(Box::new(T::deserialize(bytes)?))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use bytes::Buf;
+
+  struct Temperature {
+    celsius: u16,
+  }
+
+  impl Deserializable for Temperature {
+    fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+      let celsius = bytes.get_u16_le();
+      Ok(Self { celsius })
+    }
+  }
+
+  fn packet(message_type: u16) -> IncomingPacket {
+    let mut bytes = vec![0_u8; 38];
+    bytes[0] = 38;
+    bytes[32..34].copy_from_slice(&message_type.to_le_bytes());
+    bytes[36..38].copy_from_slice(&21_u16.to_le_bytes());
+    IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap()
+  }
+
+  #[test]
+  fn should_keep_unknown_payload() {
+    match packet(9000).decode().unwrap() {
+      Message::Unknown {
+        message_type,
+        payload,
+      } => {
+        assert_eq!(message_type, 9000);
+        assert_eq!(payload.to_vec(), vec![21, 0]);
+      }
+      _ => panic!("expected Unknown"),
+    }
+  }
+
+  #[test]
+  fn should_use_registered_decoder() {
+    let mut decoders = Decoders::default();
+    decoders.register::<Temperature>(9000).unwrap();
+    match decoders.decode(&packet(9000)).unwrap() {
+      Message::Custom {
+        message_type,
+        payload,
+      } => {
+        assert_eq!(message_type, 9000);
+        assert_eq!(payload.downcast_ref::<Temperature>().unwrap().celsius, 21);
+      }
+      _ => panic!("expected Custom"),
+    }
+  }
+
+  #[test]
+  fn should_not_replace_known_types() {
+    let mut decoders = Decoders::default();
+    assert!(decoders.register::<Temperature>(2).is_err());
+  }
+}
@@ -1,20 +1,75 @@
 use crate::message::{
-  EchoPayload, FirmwarePayload, GroupPayload, LocationPayload, StateHostInfoPayload,
-  StateInfoPayload, StateServicePayload, StateVersionPayload, StateWifiInfoPayload,
+  EchoPayload, FirmwarePayload, GroupPayload, Label, LabelPayload, LifxUuid, LocationPayload,
+  StateHostInfoPayload, StateInfoPayload, StateServicePayload, StateVersionPayload,
+  StateWifiInfoPayload,
 };
+use crate::proto::serialize::ensure_remaining;
 use crate::proto::{Deserializable, Serializable};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
+impl Serializable for Label {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_slice(&self.to_bytes());
+    Ok(())
+  }
+}
+
+impl Deserializable for Label {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, Label::SIZE)?;
+    let mut label = [0_u8; Label::SIZE];
+    bytes.copy_to_slice(&mut label);
+    Ok(Label::from(label))
+  }
+}
+
+impl Serializable for LifxUuid {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_slice(self.as_bytes());
+    Ok(())
+  }
+}
+
+impl Deserializable for LifxUuid {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 16)?;
+    let mut uuid = [0_u8; 16];
+    bytes.copy_to_slice(&mut uuid);
+    Ok(LifxUuid::from_bytes(uuid))
+  }
+}
+
 impl Deserializable for StateServicePayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 5)?;
     let service = bytes.get_u8();
     let port = bytes.get_u32_le();
     Ok(Self { service, port })
   }
 }
 
+impl Serializable for StateServicePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.service);
+    bytes.put_u32_le(self.port);
+    Ok(())
+  }
+}
+
+impl Serializable for StateHostInfoPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_f32_le(self.signal);
+    bytes.put_u32_le(self.tx);
+    bytes.put_u32_le(self.rx);
+    // reserve 2 bytes
+    bytes.put_i16_le(0);
+    Ok(())
+  }
+}
+
 impl Deserializable for StateHostInfoPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 12)?;
     let signal = bytes.get_f32_le();
     let tx = bytes.get_u32_le();
     let rx = bytes.get_u32_le();
@@ -24,6 +79,7 @@ impl Deserializable for StateHostInfoPayload {
 
 impl Deserializable for FirmwarePayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 20)?;
     let build = bytes.get_u64_le();
     // skip 8 bytes
     bytes.advance(8);
@@ -51,8 +107,20 @@ impl Serializable for FirmwarePayload {
   }
 }
 
+impl Serializable for StateWifiInfoPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_f32_le(self.signal);
+    bytes.put_u32_le(self.tx);
+    bytes.put_u32_le(self.rx);
+    // reserve 2 bytes
+    bytes.put_i16_le(0);
+    Ok(())
+  }
+}
+
 impl Deserializable for StateWifiInfoPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 12)?;
     let signal = bytes.get_f32_le();
     let tx = bytes.get_u32_le();
     let rx = bytes.get_u32_le();
@@ -60,8 +128,18 @@ impl Deserializable for StateWifiInfoPayload {
   }
 }
 
+impl Serializable for StateVersionPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u32_le(self.vendor);
+    bytes.put_u32_le(self.product);
+    bytes.put_u32_le(self.version);
+    Ok(())
+  }
+}
+
 impl Deserializable for StateVersionPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 12)?;
     let vendor = bytes.get_u32_le();
     let product = bytes.get_u32_le();
     let version = bytes.get_u32_le();
@@ -73,8 +151,18 @@ impl Deserializable for StateVersionPayload {
   }
 }
 
+impl Serializable for StateInfoPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u64_le(self.time);
+    bytes.put_u64_le(self.uptime);
+    bytes.put_u64_le(self.downtime);
+    Ok(())
+  }
+}
+
 impl Deserializable for StateInfoPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 24)?;
     let time = bytes.get_u64_le();
     let uptime = bytes.get_u64_le();
     let downtime = bytes.get_u64_le();
@@ -86,13 +174,24 @@ impl Deserializable for StateInfoPayload {
   }
 }
 
-impl Deserializable for LocationPayload {
+impl Deserializable for LabelPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
-    let mut location = [0_u8; 16];
-    bytes.copy_to_slice(&mut location);
+    let label = Label::deserialize(bytes)?;
+    Ok(Self { label })
+  }
+}
 
-    let mut label = [0_u8; 32];
-    bytes.copy_to_slice(&mut label);
+impl Serializable for LabelPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    self.label.serialize(bytes)
+  }
+}
+
+impl Deserializable for LocationPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 56)?;
+    let location = LifxUuid::deserialize(bytes)?;
+    let label = Label::deserialize(bytes)?;
 
     let updated_at = bytes.get_u64_le();
 
@@ -104,13 +203,20 @@ impl Deserializable for LocationPayload {
   }
 }
 
+impl Serializable for LocationPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    self.location.serialize(bytes)?;
+    self.label.serialize(bytes)?;
+    bytes.put_u64_le(self.updated_at);
+    Ok(())
+  }
+}
+
 impl Deserializable for GroupPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
-    let mut group = [0_u8; 16];
-    bytes.copy_to_slice(&mut group);
-
-    let mut label = [0_u8; 32];
-    bytes.copy_to_slice(&mut label);
+    ensure_remaining(bytes, 56)?;
+    let group = LifxUuid::deserialize(bytes)?;
+    let label = Label::deserialize(bytes)?;
 
     // todo - docs say i64??
     let updated_at = bytes.get_u64_le();
@@ -125,8 +231,8 @@ impl Deserializable for GroupPayload {
 
 impl Serializable for GroupPayload {
   fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
-    bytes.copy_from_slice(&self.group);
-    bytes.copy_from_slice(&self.label);
+    self.group.serialize(bytes)?;
+    self.label.serialize(bytes)?;
     bytes.put_u64_le(self.updated_at);
     Ok(())
   }
@@ -134,6 +240,7 @@ impl Serializable for GroupPayload {
 
 impl Deserializable for EchoPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 64)?;
     let mut payload = [0_u8; 64];
     bytes.copy_to_slice(&mut payload);
 
@@ -143,7 +250,7 @@ impl Deserializable for EchoPayload {
 
 impl Serializable for EchoPayload {
   fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
-    bytes.copy_from_slice(&self.payload);
+    bytes.put_slice(&self.payload);
     Ok(())
   }
 }
@@ -201,4 +308,72 @@ mod tests {
     assert_eq!(deserialized.version_minor, 55);
     assert_eq!(deserialized.version_major, 55);
   }
+
+  #[test]
+  fn test_label_round_trip() {
+    let payload = LabelPayload::new("Kitchen".into());
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 32);
+    assert_eq!(&bytes[..8], b"Kitchen\0");
+
+    let deserialized = LabelPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.label, payload.label);
+  }
+
+  #[test]
+  fn test_label_truncated() {
+    let payload = LabelPayload::new("a".repeat(33).into());
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 32);
+  }
+
+  #[test]
+  fn test_location_round_trip() {
+    let location = LifxUuid::from_bytes([7; 16]);
+    let payload = LocationPayload::new(location, "Home".into());
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 56);
+
+    let deserialized = LocationPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.location, location);
+    assert_eq!(deserialized.label, payload.label);
+    assert_eq!(deserialized.updated_at, payload.updated_at);
+    assert!(deserialized.updated_at > 0);
+  }
+
+  #[test]
+  fn test_group_round_trip() {
+    let group = LifxUuid::from_bytes([3; 16]);
+    let payload = GroupPayload::new(group, "Downstairs".into());
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 56);
+
+    let deserialized = GroupPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.group, group);
+    assert_eq!(deserialized.label, payload.label);
+    assert_eq!(deserialized.updated_at, payload.updated_at);
+  }
+
+  #[test]
+  fn test_echo_round_trip() {
+    let payload = EchoPayload { payload: [9; 64] };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+
+    let deserialized = EchoPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.payload, [9; 64]);
+  }
+  #[test]
+  fn should_decode_groups_with_invalid_labels() {
+    let mut payload = vec![0_u8; 56];
+    payload[16..19].copy_from_slice(&[b'D', 0xff, b'n']);
+    payload[48] = 42;
+    let group = GroupPayload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(group.label.as_str(), "D\u{fffd}n");
+    assert_eq!(group.updated_at, 42);
+  }
 }
@@ -0,0 +1,332 @@
+use crate::message::{
+  Color, GetTileEffectPayload, MoveDirection, MultiZoneEffect, MultiZoneEffectPayload,
+  SetTileEffectPayload, SkyType, StateTileEffectPayload, TileEffect, TileEffectPayload,
+  PALETTE_COLORS,
+};
+use crate::proto::color::BLANK;
+use crate::proto::serialize::ensure_remaining;
+use crate::proto::{Deserializable, Serializable};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::TryFrom;
+
+/// Every effect carries 32 bytes of parameters, whatever the effect uses.
+const PARAMETERS_SIZE: usize = 32;
+
+const MULTIZONE_OFF: u8 = 0;
+const MULTIZONE_MOVE: u8 = 1;
+
+const TILE_OFF: u8 = 0;
+const TILE_MORPH: u8 = 2;
+const TILE_FLAME: u8 = 3;
+const TILE_SKY: u8 = 5;
+
+fn unknown_effect(effect_type: u8) -> anyhow::Error {
+  anyhow::Error::msg(format!("Unknown effect type {}", effect_type))
+}
+
+impl MultiZoneEffect {
+  fn effect_type(&self) -> u8 {
+    match self {
+      MultiZoneEffect::Off => MULTIZONE_OFF,
+      MultiZoneEffect::Move(_) => MULTIZONE_MOVE,
+    }
+  }
+
+  fn serialize_parameters(&self, bytes: &mut BytesMut) {
+    let mut parameters = BytesMut::with_capacity(PARAMETERS_SIZE);
+    if let MultiZoneEffect::Move(direction) = self {
+      // the first parameter is reserved
+      parameters.put_u32_le(0);
+      parameters.put_u32_le((*direction).into());
+    }
+    parameters.resize(PARAMETERS_SIZE, 0);
+    bytes.put_slice(&parameters);
+  }
+
+  fn deserialize_parameters(effect_type: u8, bytes: &mut Bytes) -> anyhow::Result<Self> {
+    let mut parameters = bytes.split_to(PARAMETERS_SIZE);
+    match effect_type {
+      MULTIZONE_OFF => Ok(MultiZoneEffect::Off),
+      MULTIZONE_MOVE => {
+        parameters.advance(4);
+        let direction = MoveDirection::try_from(parameters.get_u32_le())?;
+        Ok(MultiZoneEffect::Move(direction))
+      }
+      _ => Err(unknown_effect(effect_type)),
+    }
+  }
+}
+
+impl Serializable for MultiZoneEffectPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u32_le(self.instance_id);
+    bytes.put_u8(self.effect.effect_type());
+    // reserve 2 bytes
+    bytes.put_u16_le(0);
+    bytes.put_u32_le(self.speed);
+    bytes.put_u64_le(self.duration);
+    // reserve 8 bytes
+    bytes.put_u64_le(0);
+    self.effect.serialize_parameters(bytes);
+    Ok(())
+  }
+}
+
+impl Deserializable for MultiZoneEffectPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 59)?;
+    let instance_id = bytes.get_u32_le();
+    let effect_type = bytes.get_u8();
+    // skip 2 bytes
+    bytes.advance(2);
+    let speed = bytes.get_u32_le();
+    let duration = bytes.get_u64_le();
+    // skip 8 bytes
+    bytes.advance(8);
+    let effect = MultiZoneEffect::deserialize_parameters(effect_type, bytes)?;
+    Ok(Self {
+      instance_id,
+      effect,
+      speed,
+      duration,
+    })
+  }
+}
+
+impl TileEffect {
+  fn effect_type(&self) -> u8 {
+    match self {
+      TileEffect::Off => TILE_OFF,
+      TileEffect::Morph => TILE_MORPH,
+      TileEffect::Flame => TILE_FLAME,
+      TileEffect::Sky { .. } => TILE_SKY,
+    }
+  }
+
+  fn serialize_parameters(&self, bytes: &mut BytesMut) {
+    let mut parameters = BytesMut::with_capacity(PARAMETERS_SIZE);
+    if let TileEffect::Sky {
+      sky_type,
+      cloud_saturation_min,
+      cloud_saturation_max,
+    } = self
+    {
+      parameters.put_u8((*sky_type).into());
+      parameters.put_slice(&[0; 3]);
+      parameters.put_u8(*cloud_saturation_min);
+      parameters.put_slice(&[0; 3]);
+      parameters.put_u8(*cloud_saturation_max);
+    }
+    parameters.resize(PARAMETERS_SIZE, 0);
+    bytes.put_slice(&parameters);
+  }
+
+  fn deserialize_parameters(effect_type: u8, bytes: &mut Bytes) -> anyhow::Result<Self> {
+    let mut parameters = bytes.split_to(PARAMETERS_SIZE);
+    match effect_type {
+      TILE_OFF => Ok(TileEffect::Off),
+      TILE_MORPH => Ok(TileEffect::Morph),
+      TILE_FLAME => Ok(TileEffect::Flame),
+      TILE_SKY => {
+        let sky_type = SkyType::try_from(parameters.get_u8())?;
+        parameters.advance(3);
+        let cloud_saturation_min = parameters.get_u8();
+        parameters.advance(3);
+        let cloud_saturation_max = parameters.get_u8();
+        Ok(TileEffect::Sky {
+          sky_type,
+          cloud_saturation_min,
+          cloud_saturation_max,
+        })
+      }
+      _ => Err(unknown_effect(effect_type)),
+    }
+  }
+}
+
+impl Serializable for TileEffectPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    if self.palette.len() > PALETTE_COLORS {
+      return Err(anyhow::Error::msg(format!(
+        "Unable to send {} palette colors, at most {} are allowed",
+        self.palette.len(),
+        PALETTE_COLORS
+      )));
+    }
+    bytes.put_u32_le(self.instance_id);
+    bytes.put_u8(self.effect.effect_type());
+    bytes.put_u32_le(self.speed);
+    bytes.put_u64_le(self.duration);
+    // reserve 8 bytes
+    bytes.put_u64_le(0);
+    self.effect.serialize_parameters(bytes);
+    bytes.put_u8(self.palette.len() as u8);
+    for color in &self.palette {
+      color.serialize(bytes)?;
+    }
+    for _ in self.palette.len()..PALETTE_COLORS {
+      BLANK.serialize(bytes)?;
+    }
+    Ok(())
+  }
+}
+
+impl Deserializable for TileEffectPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 186)?;
+    let instance_id = bytes.get_u32_le();
+    let effect_type = bytes.get_u8();
+    let speed = bytes.get_u32_le();
+    let duration = bytes.get_u64_le();
+    // skip 8 bytes
+    bytes.advance(8);
+    let effect = TileEffect::deserialize_parameters(effect_type, bytes)?;
+    let palette_count = bytes.get_u8();
+    let mut palette = Vec::with_capacity(PALETTE_COLORS);
+    for _ in 0..PALETTE_COLORS {
+      palette.push(Color::deserialize(bytes)?);
+    }
+    palette.truncate(palette_count as usize);
+    Ok(Self {
+      instance_id,
+      effect,
+      speed,
+      duration,
+      palette,
+    })
+  }
+}
+
+impl Serializable for GetTileEffectPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    // reserve 2 bytes
+    bytes.put_u16_le(0);
+    Ok(())
+  }
+}
+
+impl Deserializable for GetTileEffectPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 2)?;
+    // skip 2 bytes
+    bytes.advance(2);
+    Ok(Self {})
+  }
+}
+
+impl Serializable for SetTileEffectPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    // reserve 2 bytes
+    bytes.put_u16_le(0);
+    self.0.serialize(bytes)
+  }
+}
+
+impl Deserializable for SetTileEffectPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 2)?;
+    // skip 2 bytes
+    bytes.advance(2);
+    Ok(Self(TileEffectPayload::deserialize(bytes)?))
+  }
+}
+
+impl Serializable for StateTileEffectPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    // reserve u8
+    bytes.put_u8(0);
+    self.0.serialize(bytes)
+  }
+}
+
+impl Deserializable for StateTileEffectPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 1)?;
+    // skip u8
+    bytes.advance(1);
+    Ok(Self(TileEffectPayload::deserialize(bytes)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn test_multizone_effect_round_trip() {
+    let payload = MultiZoneEffectPayload {
+      instance_id: 42,
+      effect: MultiZoneEffect::Move(MoveDirection::Left),
+      speed: 3000,
+      duration: 0,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 59);
+    assert_eq!(bytes[4], MULTIZONE_MOVE);
+    assert_eq!(&bytes[27..35], &[0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0]);
+
+    let deserialized = MultiZoneEffectPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.instance_id, 42);
+    assert_eq!(deserialized.effect, payload.effect);
+    assert_eq!(deserialized.speed, 3000);
+    assert_eq!(deserialized.duration, 0);
+  }
+  #[test]
+  fn test_set_tile_effect_serialize() {
+    let payload = SetTileEffectPayload(TileEffectPayload {
+      instance_id: 7,
+      effect: TileEffect::Sky {
+        sky_type: SkyType::Clouds,
+        cloud_saturation_min: 50,
+        cloud_saturation_max: 180,
+      },
+      speed: 5000,
+      duration: 0,
+      palette: vec![Color::new(120, 100, 100, 3500)],
+    });
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 188);
+    assert_eq!(bytes[6], TILE_SKY);
+    assert_eq!(
+      &bytes[27..36],
+      &[0x2, 0x0, 0x0, 0x0, 0x32, 0x0, 0x0, 0x0, 0xb4]
+    );
+    assert_eq!(bytes[59], 1);
+    assert_eq!(
+      &bytes[60..68],
+      &[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]
+    );
+  }
+  #[test]
+  fn test_state_tile_effect_deserialize() {
+    let payload = SetTileEffectPayload(TileEffectPayload {
+      instance_id: 7,
+      effect: TileEffect::Flame,
+      speed: 4000,
+      duration: 1_000_000_000,
+      palette: vec![],
+    });
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    // state only reserves a single byte before the effect
+    let mut bytes = bytes.split_off(1).to_bytes();
+
+    let StateTileEffectPayload(deserialized) =
+      StateTileEffectPayload::deserialize(&mut bytes).unwrap();
+    assert_eq!(deserialized.instance_id, 7);
+    assert_eq!(deserialized.effect, TileEffect::Flame);
+    assert_eq!(deserialized.speed, 4000);
+    assert_eq!(deserialized.duration, 1_000_000_000);
+    assert!(deserialized.palette.is_empty());
+  }
+  #[test]
+  fn test_tile_effect_rejects_unknown_type() {
+    let mut bytes = BytesMut::new();
+    bytes.put_u8(0);
+    bytes.put_u32_le(1);
+    bytes.put_u8(4);
+    bytes.resize(187, 0);
+    assert!(StateTileEffectPayload::deserialize(&mut bytes.to_bytes()).is_err());
+  }
+}
@@ -1,53 +1,76 @@
-use super::message::MessageType;
-use super::serialize::{Deserializable, Serializable};
+use super::serialize::{ensure_remaining, Deserializable, Serializable};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
-use std::convert::TryFrom;
+
+/// Bytes taken by the header at the start of every packet.
+pub(crate) const HEADER_SIZE: usize = 36;
 
 const ADDRESSABLE: u16 = 0b0001_0000_0000_0000;
-const PROTOCOL: u16 = 0b0000_0100_0000_0000;
+pub(crate) const PROTOCOL: u16 = 0b0000_0100_0000_0000;
 const TAGGED: u16 = 0b0010_0000_0000_0000;
+const PROTOCOL_MASK: u16 = 0b0000_1111_1111_1111;
 
 const RESPONSE_REQUIRED: u8 = 0b0000_0001;
 const ACKNOWLEGEMENT_REQUIRED: u8 = 0b0000_0010;
 
-pub(crate) struct Header {
-  pub size: u16,
-  pub proto: u16,
-  pub addressable: bool,
-  pub tagged: bool,
-  pub source: u32,
-
-  pub target: u64,
-  pub res_required: bool,
-  pub ack_required: bool,
-  pub sequence: u8,
-
-  pub message_type: MessageType,
+/// The frame, frame address and protocol header at the start of every packet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+  pub(crate) size: u16,
+  pub(crate) proto: u16,
+  pub(crate) addressable: bool,
+  pub(crate) tagged: bool,
+  pub(crate) source: u32,
+
+  pub(crate) target: u64,
+  pub(crate) res_required: bool,
+  pub(crate) ack_required: bool,
+  pub(crate) sequence: u8,
+
+  pub(crate) message_type: u16,
 }
 
 impl Header {
-  pub fn new(
-    size: u16,
-    tagged: bool,
-    source: u32,
-    target: u64,
-    ack_required: bool,
-    res_required: bool,
-    sequence: u8,
-    message_type: MessageType,
-  ) -> Header {
-    Header {
-      size,
-      proto: PROTOCOL,
-      addressable: true,
-      tagged,
-      source,
-      target,
-      ack_required,
-      res_required,
-      sequence,
-      message_type,
-    }
+  /// Size of the whole packet, header included.
+  pub fn size(&self) -> u16 {
+    self.size
+  }
+
+  pub fn protocol(&self) -> u16 {
+    self.proto
+  }
+
+  pub fn addressable(&self) -> bool {
+    self.addressable
+  }
+
+  /// Set when the packet is addressed to every device rather than `target`.
+  pub fn tagged(&self) -> bool {
+    self.tagged
+  }
+
+  pub fn source(&self) -> u32 {
+    self.source
+  }
+
+  pub fn target(&self) -> u64 {
+    self.target
+  }
+
+  pub fn res_required(&self) -> bool {
+    self.res_required
+  }
+
+  pub fn ack_required(&self) -> bool {
+    self.ack_required
+  }
+
+  pub fn sequence(&self) -> u8 {
+    self.sequence
+  }
+
+  /// The message type as it appears on the wire.
+  pub fn message_type(&self) -> u16 {
+    self.message_type
   }
 }
 
@@ -93,7 +116,7 @@ impl Serializable for Header {
     for _ in 0..8 {
       bytes.put_u8(0);
     }
-    bytes.put_u16_le(self.message_type.into());
+    bytes.put_u16_le(self.message_type);
     // reserve 2 bytes
     for _ in 0..2 {
       bytes.put_u8(0);
@@ -104,11 +127,13 @@ impl Serializable for Header {
 
 impl Deserializable for Header {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, HEADER_SIZE)?;
     // --- Frame
 
     let size = bytes.get_u16_le();
 
     let flags = bytes.get_u16_le();
+    let proto = flags & PROTOCOL_MASK;
     let tagged = (flags & TAGGED) > 0;
     let addressable = (flags & ADDRESSABLE) > 0;
 
@@ -131,13 +156,12 @@ impl Deserializable for Header {
     // skip 8 bytes
     bytes.advance(8);
     let message_type = bytes.get_u16_le();
-    let message_type = MessageType::try_from(message_type)?;
     // skip 2 bytes
     bytes.advance(2);
 
     Ok(Header {
       size,
-      proto: PROTOCOL,
+      proto,
       addressable,
       tagged,
       source,
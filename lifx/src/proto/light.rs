@@ -1,4 +1,9 @@
-use crate::message::{Color, SetColorPayload, SetPowerPayload, StatePayload, StatePowerPayload};
+use crate::message::{
+  Color, HevCycleConfigurationPayload, InfraredPayload, Label, LastHevCycleResult, SetColorPayload,
+  SetHevCyclePayload, SetPowerPayload, SetWaveformOptionalPayload, SetWaveformPayload,
+  StateHevCyclePayload, StateLastHevCycleResultPayload, StatePayload, StatePowerPayload, Waveform,
+};
+use crate::proto::serialize::ensure_remaining;
 use crate::proto::{Deserializable, Power, Serializable};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use std::convert::TryFrom;
@@ -13,17 +18,125 @@ impl Serializable for SetColorPayload {
   }
 }
 
+impl Deserializable for SetColorPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 13)?;
+    // skip reserved u8
+    bytes.advance(1);
+    let color = Color::deserialize(bytes)?;
+    let duration = bytes.get_u32_le();
+    Ok(Self { color, duration })
+  }
+}
+
+impl Serializable for SetWaveformPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u8(self.transient as u8);
+    self.color.serialize(bytes)?;
+    bytes.put_u32_le(self.period);
+    bytes.put_f32_le(self.cycles);
+    bytes.put_i16_le(self.skew_ratio);
+    bytes.put_u8(self.waveform.into());
+    Ok(())
+  }
+}
+
+impl Deserializable for SetWaveformPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 21)?;
+    // skip reserved u8
+    bytes.advance(1);
+    let transient = bytes.get_u8() > 0;
+    let color = Color::deserialize(bytes)?;
+    let period = bytes.get_u32_le();
+    let cycles = bytes.get_f32_le();
+    let skew_ratio = bytes.get_i16_le();
+    let waveform = Waveform::try_from(bytes.get_u8())?;
+    Ok(Self {
+      transient,
+      color,
+      period,
+      cycles,
+      skew_ratio,
+      waveform,
+    })
+  }
+}
+
+impl Serializable for SetWaveformOptionalPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u8(self.transient as u8);
+    self.color.serialize(bytes)?;
+    bytes.put_u32_le(self.period);
+    bytes.put_f32_le(self.cycles);
+    bytes.put_i16_le(self.skew_ratio);
+    bytes.put_u8(self.waveform.into());
+    bytes.put_u8(self.set_hue as u8);
+    bytes.put_u8(self.set_saturation as u8);
+    bytes.put_u8(self.set_brightness as u8);
+    bytes.put_u8(self.set_kelvin as u8);
+    Ok(())
+  }
+}
+
+impl Deserializable for SetWaveformOptionalPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 25)?;
+    // skip reserved u8
+    bytes.advance(1);
+    let transient = bytes.get_u8() > 0;
+    let color = Color::deserialize(bytes)?;
+    let period = bytes.get_u32_le();
+    let cycles = bytes.get_f32_le();
+    let skew_ratio = bytes.get_i16_le();
+    let waveform = Waveform::try_from(bytes.get_u8())?;
+    let set_hue = bytes.get_u8() > 0;
+    let set_saturation = bytes.get_u8() > 0;
+    let set_brightness = bytes.get_u8() > 0;
+    let set_kelvin = bytes.get_u8() > 0;
+    Ok(Self {
+      transient,
+      color,
+      period,
+      cycles,
+      skew_ratio,
+      waveform,
+      set_hue,
+      set_saturation,
+      set_brightness,
+      set_kelvin,
+    })
+  }
+}
+
+impl Serializable for StatePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    self.color.serialize(bytes)?;
+    // reserve 2 bytes
+    bytes.put_i16_le(0);
+    bytes.put_u16_le(self.power);
+    self.label.serialize(bytes)?;
+    // reserve 8 bytes
+    bytes.put_u64_le(0);
+    Ok(())
+  }
+}
+
 impl Deserializable for StatePayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 52)?;
     let color = Color::deserialize(bytes)?;
     // skip 2 bytes
     bytes.advance(2);
     let power = bytes.get_u16_le();
 
-    let mut label = [0_u8; 32];
-    bytes.copy_to_slice(&mut label);
+    let label = Label::deserialize(bytes)?;
 
-    // skip 2 bytes
+    // skip 8 bytes
     bytes.advance(8);
     Ok(Self {
       color,
@@ -41,10 +154,228 @@ impl Serializable for SetPowerPayload {
   }
 }
 
+impl Deserializable for SetPowerPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 6)?;
+    let level = bytes.get_u16_le();
+    let level = Power::try_from(level)?;
+    let duration = bytes.get_u32_le();
+    Ok(Self { level, duration })
+  }
+}
+
+impl Serializable for StatePowerPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u16_le(self.level.into());
+    Ok(())
+  }
+}
+
 impl Deserializable for StatePowerPayload {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 2)?;
     let level = bytes.get_u16_le();
     let level = Power::try_from(level)?;
     Ok(Self { level })
   }
 }
+
+impl Serializable for InfraredPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u16_le(self.brightness);
+    Ok(())
+  }
+}
+
+impl Deserializable for InfraredPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 2)?;
+    let brightness = bytes.get_u16_le();
+    Ok(Self { brightness })
+  }
+}
+
+impl Serializable for SetHevCyclePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.enable as u8);
+    bytes.put_u32_le(self.duration_s);
+    Ok(())
+  }
+}
+
+impl Deserializable for SetHevCyclePayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 5)?;
+    let enable = bytes.get_u8() > 0;
+    let duration_s = bytes.get_u32_le();
+    Ok(Self { enable, duration_s })
+  }
+}
+
+impl Serializable for StateHevCyclePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u32_le(self.duration_s);
+    bytes.put_u32_le(self.remaining_s);
+    bytes.put_u8(self.last_power as u8);
+    Ok(())
+  }
+}
+
+impl Deserializable for StateHevCyclePayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 9)?;
+    let duration_s = bytes.get_u32_le();
+    let remaining_s = bytes.get_u32_le();
+    let last_power = bytes.get_u8() > 0;
+    Ok(Self {
+      duration_s,
+      remaining_s,
+      last_power,
+    })
+  }
+}
+
+impl Serializable for HevCycleConfigurationPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.indication as u8);
+    bytes.put_u32_le(self.duration_s);
+    Ok(())
+  }
+}
+
+impl Deserializable for HevCycleConfigurationPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 5)?;
+    let indication = bytes.get_u8() > 0;
+    let duration_s = bytes.get_u32_le();
+    Ok(Self {
+      indication,
+      duration_s,
+    })
+  }
+}
+
+impl Serializable for StateLastHevCycleResultPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.result.into());
+    Ok(())
+  }
+}
+
+impl Deserializable for StateLastHevCycleResultPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 1)?;
+    let result = LastHevCycleResult::try_from(bytes.get_u8())?;
+    Ok(Self { result })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn test_set_waveform_serialize() {
+    let payload = SetWaveformPayload {
+      transient: true,
+      color: Color::new(120, 100, 100, 3500),
+      period: 1000,
+      cycles: 5.0,
+      skew_ratio: 0,
+      waveform: Waveform::Pulse,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(
+      bytes,
+      vec![
+        0x0, 0x1, 0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0,
+        0xa0, 0x40, 0x0, 0x0, 0x4
+      ]
+    );
+  }
+  #[test]
+  fn test_set_waveform_optional_round_trip() {
+    let payload = SetWaveformOptionalPayload {
+      transient: false,
+      color: Color::new(0, 0, 50, 2700),
+      period: 250,
+      cycles: 2.5,
+      skew_ratio: -16384,
+      waveform: Waveform::HalfSine,
+      set_hue: false,
+      set_saturation: false,
+      set_brightness: true,
+      set_kelvin: false,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 25);
+
+    let deserialized = SetWaveformOptionalPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert!(!deserialized.transient);
+    assert_eq!(deserialized.color, payload.color);
+    assert_eq!(deserialized.period, 250);
+    assert_eq!(deserialized.cycles, 2.5);
+    assert_eq!(deserialized.skew_ratio, -16384);
+    assert_eq!(deserialized.waveform, Waveform::HalfSine);
+    assert!(deserialized.set_brightness);
+    assert!(!deserialized.set_hue && !deserialized.set_saturation && !deserialized.set_kelvin);
+  }
+  #[test]
+  fn test_waveform_rejects_unknown_type() {
+    let payload: &[u8] = &[
+      0x0, 0x1, 0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0xa0,
+      0x40, 0x0, 0x0, 0x9,
+    ];
+    assert!(SetWaveformPayload::deserialize(&mut Bytes::from(payload)).is_err());
+  }
+  #[test]
+  fn test_infrared_serialize() {
+    let payload = InfraredPayload::new(100);
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes, vec![0xff, 0xff]);
+  }
+  #[test]
+  fn test_infrared_round_trip() {
+    for brightness in 0..=100 {
+      let payload = InfraredPayload::new(brightness);
+      let mut bytes = BytesMut::new();
+      payload.serialize(&mut bytes).unwrap();
+      let deserialized = InfraredPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+      assert_eq!(deserialized.brightness, payload.brightness);
+      assert_eq!(deserialized.brightness(), brightness);
+    }
+  }
+  #[test]
+  fn test_set_hev_cycle_serialize() {
+    let payload = SetHevCyclePayload {
+      enable: true,
+      duration_s: 7200,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes, vec![0x1, 0x20, 0x1c, 0x0, 0x0]);
+  }
+  #[test]
+  fn test_state_hev_cycle_deserialize() {
+    let payload: &[u8] = &[0x20, 0x1c, 0x0, 0x0, 0x10, 0xe, 0x0, 0x0, 0x1];
+    let deserialized = StateHevCyclePayload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(deserialized.duration_s, 7200);
+    assert_eq!(deserialized.remaining_s, 3600);
+    assert!(deserialized.last_power);
+    assert!(deserialized.is_running());
+  }
+  #[test]
+  fn test_last_hev_cycle_result_deserialize() {
+    let payload: &[u8] = &[0x4];
+    let deserialized =
+      StateLastHevCycleResultPayload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(deserialized.result, LastHevCycleResult::InterruptedByLan);
+
+    let payload: &[u8] = &[0xff];
+    let deserialized =
+      StateLastHevCycleResultPayload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(deserialized.result, LastHevCycleResult::None);
+  }
+}
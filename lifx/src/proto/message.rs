@@ -1,4 +1,9 @@
+use crate::error::DecodeError;
+use crate::message::*;
+use crate::proto::Deserializable;
+use bytes::Bytes;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::any::Any;
 use std::fmt;
 
 #[repr(u16)]
@@ -31,20 +36,361 @@ pub enum MessageType {
 
   Get = 101,
   SetColor = 102,
+  SetWaveform = 103,
   State = 107,
 
   GetPower = 116,
   SetPower = 117,
   StatePower = 118,
+  SetWaveformOptional = 119,
+  GetInfrared = 120,
+  StateInfrared = 121,
+  SetInfrared = 122,
+
+  GetHevCycle = 142,
+  SetHevCycle = 143,
+  StateHevCycle = 144,
+  GetHevCycleConfiguration = 145,
+  SetHevCycleConfiguration = 146,
+  StateHevCycleConfiguration = 147,
+  GetLastHevCycleResult = 148,
+  StateLastHevCycleResult = 149,
+
+  SetColorZones = 501,
+  GetColorZones = 502,
+  StateZone = 503,
+  StateMultiZone = 506,
+  GetMultiZoneEffect = 507,
+  SetMultiZoneEffect = 508,
+  StateMultiZoneEffect = 509,
+  SetExtendedColorZones = 510,
+  GetExtendedColorZones = 511,
+  StateExtendedColorZones = 512,
+
+  GetDeviceChain = 701,
+  StateDeviceChain = 702,
+  SetUserPosition = 703,
+  Get64 = 707,
+  State64 = 711,
+  Set64 = 715,
+  GetTileEffect = 718,
+  SetTileEffect = 719,
+  StateTileEffect = 720,
+
+  GetRPower = 816,
+  SetRPower = 817,
+  StateRPower = 818,
+}
+
+/// A decoded message, one variant per `MessageType`.
+pub enum Message {
+  GetService,
+  StateService(StateServicePayload),
+  GetHostInfo,
+  StateHostInfo(StateHostInfoPayload),
+  GetHostFirmware,
+  StateHostFirmware(FirmwarePayload),
+  GetWifiInfo,
+  StateWifiInfo(StateWifiInfoPayload),
+  GetWifiFirmware,
+  StateWifiFirmware(FirmwarePayload),
+  GetLabel,
+  SetLabel(LabelPayload),
+  StateLabel(LabelPayload),
+  GetVersion,
+  StateVersion(StateVersionPayload),
+  GetInfo,
+  StateInfo(StateInfoPayload),
+  Acknowledgement,
+  GetLocation,
+  SetLocation(LocationPayload),
+  StateLocation(LocationPayload),
+  GetGroup,
+  SetGroup(GroupPayload),
+  StateGroup(GroupPayload),
+
+  Get,
+  SetColor(SetColorPayload),
+  SetWaveform(SetWaveformPayload),
+  State(StatePayload),
+
+  GetPower,
+  SetPower(SetPowerPayload),
+  StatePower(StatePowerPayload),
+  SetWaveformOptional(SetWaveformOptionalPayload),
+  GetInfrared,
+  StateInfrared(InfraredPayload),
+  SetInfrared(InfraredPayload),
+
+  GetHevCycle,
+  SetHevCycle(SetHevCyclePayload),
+  StateHevCycle(StateHevCyclePayload),
+  GetHevCycleConfiguration,
+  SetHevCycleConfiguration(HevCycleConfigurationPayload),
+  StateHevCycleConfiguration(HevCycleConfigurationPayload),
+  GetLastHevCycleResult,
+  StateLastHevCycleResult(StateLastHevCycleResultPayload),
+
+  SetColorZones(SetColorZonesPayload),
+  GetColorZones(GetColorZonesPayload),
+  StateZone(StateZonePayload),
+  StateMultiZone(StateMultiZonePayload),
+  GetMultiZoneEffect,
+  SetMultiZoneEffect(MultiZoneEffectPayload),
+  StateMultiZoneEffect(MultiZoneEffectPayload),
+  SetExtendedColorZones(SetExtendedColorZonesPayload),
+  GetExtendedColorZones,
+  StateExtendedColorZones(StateExtendedColorZonesPayload),
+
+  GetDeviceChain,
+  StateDeviceChain(StateDeviceChainPayload),
+  SetUserPosition(SetUserPositionPayload),
+  Get64(Get64Payload),
+  State64(State64Payload),
+  Set64(Set64Payload),
+  GetTileEffect(GetTileEffectPayload),
+  SetTileEffect(SetTileEffectPayload),
+  StateTileEffect(StateTileEffectPayload),
+
+  GetRPower(GetRPowerPayload),
+  SetRPower(SetRPowerPayload),
+  StateRPower(StateRPowerPayload),
+
+  /// A message type this crate doesn't know, with its payload left as it arrived.
+  Unknown {
+    message_type: u16,
+    payload: Bytes,
+  },
+  /// A message type this crate doesn't know, decoded by a decoder registered with `Decoders`.
+  Custom {
+    message_type: u16,
+    payload: Box<dyn Any + Send + Sync>,
+  },
+}
+
+impl Message {
+  /// Decodes the payload according to its message type.
+  pub fn decode(message_type: MessageType, bytes: &mut Bytes) -> anyhow::Result<Self> {
+    let message = match message_type {
+      MessageType::GetService => Message::GetService,
+      MessageType::StateService => Message::StateService(Deserializable::deserialize(bytes)?),
+      MessageType::GetHostInfo => Message::GetHostInfo,
+      MessageType::StateHostInfo => Message::StateHostInfo(Deserializable::deserialize(bytes)?),
+      MessageType::GetHostFirmware => Message::GetHostFirmware,
+      MessageType::StateHostFirmware => {
+        Message::StateHostFirmware(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::GetWifiInfo => Message::GetWifiInfo,
+      MessageType::StateWifiInfo => Message::StateWifiInfo(Deserializable::deserialize(bytes)?),
+      MessageType::GetWifiFirmware => Message::GetWifiFirmware,
+      MessageType::StateWifiFirmware => {
+        Message::StateWifiFirmware(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::GetLabel => Message::GetLabel,
+      MessageType::SetLabel => Message::SetLabel(Deserializable::deserialize(bytes)?),
+      MessageType::StateLabel => Message::StateLabel(Deserializable::deserialize(bytes)?),
+      MessageType::GetVersion => Message::GetVersion,
+      MessageType::StateVersion => Message::StateVersion(Deserializable::deserialize(bytes)?),
+      MessageType::GetInfo => Message::GetInfo,
+      MessageType::StateInfo => Message::StateInfo(Deserializable::deserialize(bytes)?),
+      MessageType::Acknowlegement => Message::Acknowledgement,
+      MessageType::GetLocation => Message::GetLocation,
+      MessageType::SetLocation => Message::SetLocation(Deserializable::deserialize(bytes)?),
+      MessageType::StateLocation => Message::StateLocation(Deserializable::deserialize(bytes)?),
+      MessageType::GetGroup => Message::GetGroup,
+      MessageType::SetGroup => Message::SetGroup(Deserializable::deserialize(bytes)?),
+      MessageType::StateGroup => Message::StateGroup(Deserializable::deserialize(bytes)?),
+
+      MessageType::Get => Message::Get,
+      MessageType::SetColor => Message::SetColor(Deserializable::deserialize(bytes)?),
+      MessageType::SetWaveform => Message::SetWaveform(Deserializable::deserialize(bytes)?),
+      MessageType::State => Message::State(Deserializable::deserialize(bytes)?),
+
+      MessageType::GetPower => Message::GetPower,
+      MessageType::SetPower => Message::SetPower(Deserializable::deserialize(bytes)?),
+      MessageType::StatePower => Message::StatePower(Deserializable::deserialize(bytes)?),
+      MessageType::SetWaveformOptional => {
+        Message::SetWaveformOptional(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::GetInfrared => Message::GetInfrared,
+      MessageType::StateInfrared => Message::StateInfrared(Deserializable::deserialize(bytes)?),
+      MessageType::SetInfrared => Message::SetInfrared(Deserializable::deserialize(bytes)?),
+
+      MessageType::GetHevCycle => Message::GetHevCycle,
+      MessageType::SetHevCycle => Message::SetHevCycle(Deserializable::deserialize(bytes)?),
+      MessageType::StateHevCycle => Message::StateHevCycle(Deserializable::deserialize(bytes)?),
+      MessageType::GetHevCycleConfiguration => Message::GetHevCycleConfiguration,
+      MessageType::SetHevCycleConfiguration => {
+        Message::SetHevCycleConfiguration(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::StateHevCycleConfiguration => {
+        Message::StateHevCycleConfiguration(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::GetLastHevCycleResult => Message::GetLastHevCycleResult,
+      MessageType::StateLastHevCycleResult => {
+        Message::StateLastHevCycleResult(Deserializable::deserialize(bytes)?)
+      }
+
+      MessageType::SetColorZones => Message::SetColorZones(Deserializable::deserialize(bytes)?),
+      MessageType::GetColorZones => Message::GetColorZones(Deserializable::deserialize(bytes)?),
+      MessageType::StateZone => Message::StateZone(Deserializable::deserialize(bytes)?),
+      MessageType::StateMultiZone => Message::StateMultiZone(Deserializable::deserialize(bytes)?),
+      MessageType::GetMultiZoneEffect => Message::GetMultiZoneEffect,
+      MessageType::SetMultiZoneEffect => {
+        Message::SetMultiZoneEffect(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::StateMultiZoneEffect => {
+        Message::StateMultiZoneEffect(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::SetExtendedColorZones => {
+        Message::SetExtendedColorZones(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::GetExtendedColorZones => Message::GetExtendedColorZones,
+      MessageType::StateExtendedColorZones => {
+        Message::StateExtendedColorZones(Deserializable::deserialize(bytes)?)
+      }
+
+      MessageType::GetDeviceChain => Message::GetDeviceChain,
+      MessageType::StateDeviceChain => {
+        Message::StateDeviceChain(Deserializable::deserialize(bytes)?)
+      }
+      MessageType::SetUserPosition => Message::SetUserPosition(Deserializable::deserialize(bytes)?),
+      MessageType::Get64 => Message::Get64(Deserializable::deserialize(bytes)?),
+      MessageType::State64 => Message::State64(Deserializable::deserialize(bytes)?),
+      MessageType::Set64 => Message::Set64(Deserializable::deserialize(bytes)?),
+      MessageType::GetTileEffect => Message::GetTileEffect(Deserializable::deserialize(bytes)?),
+      MessageType::SetTileEffect => Message::SetTileEffect(Deserializable::deserialize(bytes)?),
+      MessageType::StateTileEffect => Message::StateTileEffect(Deserializable::deserialize(bytes)?),
+
+      MessageType::GetRPower => Message::GetRPower(Deserializable::deserialize(bytes)?),
+      MessageType::SetRPower => Message::SetRPower(Deserializable::deserialize(bytes)?),
+      MessageType::StateRPower => Message::StateRPower(Deserializable::deserialize(bytes)?),
+    };
+    Ok(message)
+  }
+
+  pub fn message_type(&self) -> Result<MessageType, DecodeError> {
+    let message_type = match self {
+      Message::Unknown { message_type, .. } | Message::Custom { message_type, .. } => {
+        return Err(DecodeError::UnknownMessageType(*message_type))
+      }
+      Message::GetService => MessageType::GetService,
+      Message::StateService(_) => MessageType::StateService,
+      Message::GetHostInfo => MessageType::GetHostInfo,
+      Message::StateHostInfo(_) => MessageType::StateHostInfo,
+      Message::GetHostFirmware => MessageType::GetHostFirmware,
+      Message::StateHostFirmware(_) => MessageType::StateHostFirmware,
+      Message::GetWifiInfo => MessageType::GetWifiInfo,
+      Message::StateWifiInfo(_) => MessageType::StateWifiInfo,
+      Message::GetWifiFirmware => MessageType::GetWifiFirmware,
+      Message::StateWifiFirmware(_) => MessageType::StateWifiFirmware,
+      Message::GetLabel => MessageType::GetLabel,
+      Message::SetLabel(_) => MessageType::SetLabel,
+      Message::StateLabel(_) => MessageType::StateLabel,
+      Message::GetVersion => MessageType::GetVersion,
+      Message::StateVersion(_) => MessageType::StateVersion,
+      Message::GetInfo => MessageType::GetInfo,
+      Message::StateInfo(_) => MessageType::StateInfo,
+      Message::Acknowledgement => MessageType::Acknowlegement,
+      Message::GetLocation => MessageType::GetLocation,
+      Message::SetLocation(_) => MessageType::SetLocation,
+      Message::StateLocation(_) => MessageType::StateLocation,
+      Message::GetGroup => MessageType::GetGroup,
+      Message::SetGroup(_) => MessageType::SetGroup,
+      Message::StateGroup(_) => MessageType::StateGroup,
+
+      Message::Get => MessageType::Get,
+      Message::SetColor(_) => MessageType::SetColor,
+      Message::SetWaveform(_) => MessageType::SetWaveform,
+      Message::State(_) => MessageType::State,
+
+      Message::GetPower => MessageType::GetPower,
+      Message::SetPower(_) => MessageType::SetPower,
+      Message::StatePower(_) => MessageType::StatePower,
+      Message::SetWaveformOptional(_) => MessageType::SetWaveformOptional,
+      Message::GetInfrared => MessageType::GetInfrared,
+      Message::StateInfrared(_) => MessageType::StateInfrared,
+      Message::SetInfrared(_) => MessageType::SetInfrared,
+
+      Message::GetHevCycle => MessageType::GetHevCycle,
+      Message::SetHevCycle(_) => MessageType::SetHevCycle,
+      Message::StateHevCycle(_) => MessageType::StateHevCycle,
+      Message::GetHevCycleConfiguration => MessageType::GetHevCycleConfiguration,
+      Message::SetHevCycleConfiguration(_) => MessageType::SetHevCycleConfiguration,
+      Message::StateHevCycleConfiguration(_) => MessageType::StateHevCycleConfiguration,
+      Message::GetLastHevCycleResult => MessageType::GetLastHevCycleResult,
+      Message::StateLastHevCycleResult(_) => MessageType::StateLastHevCycleResult,
+
+      Message::SetColorZones(_) => MessageType::SetColorZones,
+      Message::GetColorZones(_) => MessageType::GetColorZones,
+      Message::StateZone(_) => MessageType::StateZone,
+      Message::StateMultiZone(_) => MessageType::StateMultiZone,
+      Message::GetMultiZoneEffect => MessageType::GetMultiZoneEffect,
+      Message::SetMultiZoneEffect(_) => MessageType::SetMultiZoneEffect,
+      Message::StateMultiZoneEffect(_) => MessageType::StateMultiZoneEffect,
+      Message::SetExtendedColorZones(_) => MessageType::SetExtendedColorZones,
+      Message::GetExtendedColorZones => MessageType::GetExtendedColorZones,
+      Message::StateExtendedColorZones(_) => MessageType::StateExtendedColorZones,
+
+      Message::GetDeviceChain => MessageType::GetDeviceChain,
+      Message::StateDeviceChain(_) => MessageType::StateDeviceChain,
+      Message::SetUserPosition(_) => MessageType::SetUserPosition,
+      Message::Get64(_) => MessageType::Get64,
+      Message::State64(_) => MessageType::State64,
+      Message::Set64(_) => MessageType::Set64,
+      Message::GetTileEffect(_) => MessageType::GetTileEffect,
+      Message::SetTileEffect(_) => MessageType::SetTileEffect,
+      Message::StateTileEffect(_) => MessageType::StateTileEffect,
+
+      Message::GetRPower(_) => MessageType::GetRPower,
+      Message::SetRPower(_) => MessageType::SetRPower,
+      Message::StateRPower(_) => MessageType::StateRPower,
+    };
+    Ok(message_type)
+  }
 }
 
 #[repr(u16)]
-#[derive(Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[derive(PartialEq, Debug, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
 pub enum Power {
   On = 65535,
   Off = 0,
 }
 
+impl MessageType {
+  /// Whether devices send this type, rather than receive it.
+  pub fn is_reply(self) -> bool {
+    matches!(
+      self,
+      MessageType::StateService
+        | MessageType::StateHostInfo
+        | MessageType::StateHostFirmware
+        | MessageType::StateWifiInfo
+        | MessageType::StateWifiFirmware
+        | MessageType::StateLabel
+        | MessageType::StateVersion
+        | MessageType::StateInfo
+        | MessageType::Acknowlegement
+        | MessageType::StateLocation
+        | MessageType::StateGroup
+        | MessageType::State
+        | MessageType::StatePower
+        | MessageType::StateInfrared
+        | MessageType::StateHevCycle
+        | MessageType::StateHevCycleConfiguration
+        | MessageType::StateLastHevCycleResult
+        | MessageType::StateZone
+        | MessageType::StateMultiZone
+        | MessageType::StateMultiZoneEffect
+        | MessageType::StateExtendedColorZones
+        | MessageType::StateDeviceChain
+        | MessageType::State64
+        | MessageType::StateTileEffect
+        | MessageType::StateRPower
+    )
+  }
+}
+
 impl fmt::Display for MessageType {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     fmt::Debug::fmt(self, f)
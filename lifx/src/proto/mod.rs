@@ -1,12 +1,20 @@
 mod color;
+mod decoders;
 mod device;
+mod effect;
 mod header;
 mod light;
 mod message;
+mod multizone;
 mod packet;
+mod relay;
+#[cfg(test)]
+mod roundtrip;
 mod serialize;
+mod tile;
 
-pub(crate) use header::Header;
+pub use decoders::Decoders;
+pub use header::Header;
 pub use message::*;
-pub use packet::{IncomingPacket, OutgoingPacket};
+pub use packet::{IncomingPacket, OutgoingPacket, OutgoingPacketBuilder};
 pub use serialize::*;
@@ -0,0 +1,276 @@
+use crate::message::{
+  ApplicationRequest, Color, GetColorZonesPayload, SetColorZonesPayload,
+  SetExtendedColorZonesPayload, StateExtendedColorZonesPayload, StateMultiZonePayload,
+  StateZonePayload, EXTENDED_ZONES, MULTI_ZONES,
+};
+use crate::proto::color::BLANK;
+use crate::proto::serialize::ensure_remaining;
+use crate::proto::{Deserializable, Serializable};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::TryFrom;
+
+fn serialize_colors(colors: &[Color], bytes: &mut BytesMut) -> anyhow::Result<()> {
+  if colors.len() > EXTENDED_ZONES {
+    return Err(anyhow::Error::msg(format!(
+      "Unable to send {} zones, at most {} fit in one message",
+      colors.len(),
+      EXTENDED_ZONES
+    )));
+  }
+  bytes.put_u8(colors.len() as u8);
+  for color in colors {
+    color.serialize(bytes)?;
+  }
+  // the rest of the buffer is always sent
+  for _ in colors.len()..EXTENDED_ZONES {
+    BLANK.serialize(bytes)?;
+  }
+  Ok(())
+}
+
+fn deserialize_colors(bytes: &mut Bytes) -> anyhow::Result<Vec<Color>> {
+  ensure_remaining(bytes, 1 + EXTENDED_ZONES * 8)?;
+  let colors_count = bytes.get_u8() as usize;
+  let mut colors = Vec::with_capacity(EXTENDED_ZONES);
+  for _ in 0..EXTENDED_ZONES {
+    colors.push(Color::deserialize(bytes)?);
+  }
+  colors.truncate(colors_count);
+  Ok(colors)
+}
+
+impl Serializable for SetColorZonesPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.start_index);
+    bytes.put_u8(self.end_index);
+    self.color.serialize(bytes)?;
+    bytes.put_u32_le(self.duration);
+    bytes.put_u8(self.apply.into());
+    Ok(())
+  }
+}
+
+impl Deserializable for SetColorZonesPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 15)?;
+    let start_index = bytes.get_u8();
+    let end_index = bytes.get_u8();
+    let color = Color::deserialize(bytes)?;
+    let duration = bytes.get_u32_le();
+    let apply = ApplicationRequest::deserialize(bytes)?;
+    Ok(Self {
+      start_index,
+      end_index,
+      color,
+      duration,
+      apply,
+    })
+  }
+}
+
+impl Serializable for GetColorZonesPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.start_index);
+    bytes.put_u8(self.end_index);
+    Ok(())
+  }
+}
+
+impl Deserializable for GetColorZonesPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 2)?;
+    let start_index = bytes.get_u8();
+    let end_index = bytes.get_u8();
+    Ok(Self {
+      start_index,
+      end_index,
+    })
+  }
+}
+
+impl Serializable for StateZonePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.zones_count);
+    bytes.put_u8(self.zone_index);
+    self.color.serialize(bytes)
+  }
+}
+
+impl Deserializable for StateZonePayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 10)?;
+    let zones_count = bytes.get_u8();
+    let zone_index = bytes.get_u8();
+    let color = Color::deserialize(bytes)?;
+    Ok(Self {
+      zones_count,
+      zone_index,
+      color,
+    })
+  }
+}
+
+impl Serializable for StateMultiZonePayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.zones_count);
+    bytes.put_u8(self.zone_index);
+    for color in self.colors.iter() {
+      color.serialize(bytes)?;
+    }
+    Ok(())
+  }
+}
+
+impl Deserializable for StateMultiZonePayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 66)?;
+    let zones_count = bytes.get_u8();
+    let zone_index = bytes.get_u8();
+    let mut colors = [BLANK; MULTI_ZONES];
+    for color in colors.iter_mut() {
+      *color = Color::deserialize(bytes)?;
+    }
+    Ok(Self {
+      zones_count,
+      zone_index,
+      colors,
+    })
+  }
+}
+
+impl Serializable for SetExtendedColorZonesPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u32_le(self.duration);
+    bytes.put_u8(self.apply.into());
+    bytes.put_u16_le(self.zone_index);
+    serialize_colors(&self.colors, bytes)
+  }
+}
+
+impl Deserializable for SetExtendedColorZonesPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 7)?;
+    let duration = bytes.get_u32_le();
+    let apply = ApplicationRequest::deserialize(bytes)?;
+    let zone_index = bytes.get_u16_le();
+    let colors = deserialize_colors(bytes)?;
+    Ok(Self {
+      duration,
+      apply,
+      zone_index,
+      colors,
+    })
+  }
+}
+
+impl Serializable for StateExtendedColorZonesPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u16_le(self.zones_count);
+    bytes.put_u16_le(self.zone_index);
+    serialize_colors(&self.colors, bytes)
+  }
+}
+
+impl Deserializable for StateExtendedColorZonesPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 4)?;
+    let zones_count = bytes.get_u16_le();
+    let zone_index = bytes.get_u16_le();
+    let colors = deserialize_colors(bytes)?;
+    Ok(Self {
+      zones_count,
+      zone_index,
+      colors,
+    })
+  }
+}
+
+impl Serializable for ApplicationRequest {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8((*self).into());
+    Ok(())
+  }
+}
+
+impl Deserializable for ApplicationRequest {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 1)?;
+    Ok(ApplicationRequest::try_from(bytes.get_u8())?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn test_set_color_zones_serialize() {
+    let payload = SetColorZonesPayload {
+      start_index: 2,
+      end_index: 5,
+      color: Color::new(120, 100, 100, 3500),
+      duration: 1024,
+      apply: ApplicationRequest::Apply,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(
+      bytes,
+      vec![0x2, 0x5, 0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd, 0x0, 0x4, 0x0, 0x0, 0x1]
+    );
+  }
+  #[test]
+  fn test_extended_color_zones_serialize() {
+    let payload = SetExtendedColorZonesPayload {
+      duration: 0,
+      apply: ApplicationRequest::Apply,
+      zone_index: 0,
+      colors: vec![Color::new(120, 100, 100, 3500); 3],
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 8 + EXTENDED_ZONES * 8);
+    assert_eq!(bytes[7], 3);
+    assert_eq!(
+      &bytes[8..16],
+      &[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]
+    );
+    assert!(bytes[32..].iter().all(|byte| *byte == 0));
+  }
+  #[test]
+  fn test_extended_color_zones_rejects_too_many_zones() {
+    let payload = SetExtendedColorZonesPayload {
+      duration: 0,
+      apply: ApplicationRequest::Apply,
+      zone_index: 0,
+      colors: vec![BLANK; EXTENDED_ZONES + 1],
+    };
+    assert!(payload.serialize(&mut BytesMut::new()).is_err());
+  }
+  #[test]
+  fn test_state_extended_color_zones_deserialize() {
+    let mut payload = vec![0x10, 0x0, 0x8, 0x0, 0x2];
+    payload.extend_from_slice(&[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]);
+    payload.extend_from_slice(&[0x0, 0x0, 0x0, 0x0, 0xff, 0xff, 0xac, 0xd]);
+    payload.resize(5 + EXTENDED_ZONES * 8, 0);
+    let mut bytes = Bytes::from(payload);
+    let deserialized = StateExtendedColorZonesPayload::deserialize(&mut bytes).unwrap();
+    assert_eq!(deserialized.zones_count, 16);
+    assert_eq!(deserialized.zone_index, 8);
+    assert_eq!(
+      deserialized.colors,
+      vec![Color::new(120, 100, 100, 3500), Color::new(0, 0, 100, 3500)]
+    );
+  }
+  #[test]
+  fn test_state_multi_zone_deserialize() {
+    let mut payload = vec![0x10, 0x8];
+    payload.resize(2 + MULTI_ZONES * 8, 0);
+    payload[2..10].copy_from_slice(&[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]);
+    let mut bytes = Bytes::from(payload);
+    let deserialized = StateMultiZonePayload::deserialize(&mut bytes).unwrap();
+    assert_eq!(deserialized.zones_count, 16);
+    assert_eq!(deserialized.zone_index, 8);
+    assert_eq!(deserialized.colors[0], Color::new(120, 100, 100, 3500));
+    assert_eq!(deserialized.colors[1], BLANK);
+  }
+}
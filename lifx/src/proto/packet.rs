@@ -1,12 +1,16 @@
-use super::message::MessageType;
+use super::decoders::Decoders;
+use super::header::{Header, HEADER_SIZE, PROTOCOL};
+use super::message::{Message, MessageType};
 use super::serialize::{Deserializable, Serializable};
-use super::Header;
+use crate::error::DecodeError;
 use bytes::{Buf, Bytes, BytesMut};
 
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
+#[derive(Clone)]
 pub struct OutgoingPacket {
   header: Header,
+  message_type: MessageType,
   payload: bytes::Bytes,
 }
 
@@ -14,27 +18,119 @@ impl OutgoingPacket {
   pub fn new(
     sequence: u8,
     source: u32,
+    target: u64,
     ack_required: bool,
     res_required: bool,
     message_type: MessageType,
     payload: impl Serializable,
   ) -> anyhow::Result<Self> {
+    OutgoingPacket::builder(message_type)
+      .sequence(sequence)
+      .source(source)
+      .target(target)
+      .ack_required(ack_required)
+      .res_required(res_required)
+      .build(payload)
+  }
+
+  pub fn builder(message_type: MessageType) -> OutgoingPacketBuilder {
+    OutgoingPacketBuilder {
+      sequence: 0,
+      source: 0,
+      target: 0,
+      tagged: None,
+      addressable: true,
+      ack_required: false,
+      res_required: false,
+      message_type,
+    }
+  }
+
+  pub fn header(&self) -> &Header {
+    &self.header
+  }
+
+  pub fn message_type(&self) -> MessageType {
+    self.message_type
+  }
+
+  pub fn target(&self) -> u64 {
+    self.header.target
+  }
+}
+
+/// Builds an `OutgoingPacket` field by field.
+///
+/// Unless set explicitly, a packet is tagged when it has no target and is always addressable.
+#[derive(Clone)]
+pub struct OutgoingPacketBuilder {
+  sequence: u8,
+  source: u32,
+  target: u64,
+  tagged: Option<bool>,
+  addressable: bool,
+  ack_required: bool,
+  res_required: bool,
+  message_type: MessageType,
+}
+
+impl OutgoingPacketBuilder {
+  pub fn sequence(mut self, sequence: u8) -> Self {
+    self.sequence = sequence;
+    self
+  }
+
+  pub fn source(mut self, source: u32) -> Self {
+    self.source = source;
+    self
+  }
+
+  pub fn target(mut self, target: u64) -> Self {
+    self.target = target;
+    self
+  }
+
+  pub fn tagged(mut self, tagged: bool) -> Self {
+    self.tagged = Some(tagged);
+    self
+  }
+
+  pub fn addressable(mut self, addressable: bool) -> Self {
+    self.addressable = addressable;
+    self
+  }
+
+  pub fn ack_required(mut self, ack_required: bool) -> Self {
+    self.ack_required = ack_required;
+    self
+  }
+
+  pub fn res_required(mut self, res_required: bool) -> Self {
+    self.res_required = res_required;
+    self
+  }
+
+  pub fn build(self, payload: impl Serializable) -> anyhow::Result<OutgoingPacket> {
     let mut bytes = BytesMut::new();
     payload.serialize(&mut bytes)?;
-    let len = bytes.len() as u16;
-
-    let header = Header::new(
-      36_u16 + len,
-      true,
-      source,
-      0,
-      ack_required,
-      res_required,
-      sequence,
-      message_type,
-    );
+    let size = u16::try_from(HEADER_SIZE + bytes.len())
+      .map_err(|_| anyhow::Error::msg(format!("payload of {} bytes is too large", bytes.len())))?;
+
+    let header = Header {
+      size,
+      proto: PROTOCOL,
+      addressable: self.addressable,
+      tagged: self.tagged.unwrap_or(self.target == 0),
+      source: self.source,
+      target: self.target,
+      res_required: self.res_required,
+      ack_required: self.ack_required,
+      sequence: self.sequence,
+      message_type: self.message_type.into(),
+    };
     Ok(OutgoingPacket {
       header,
+      message_type: self.message_type,
       payload: bytes.to_bytes(),
     })
   }
@@ -51,6 +147,7 @@ impl TryInto<Vec<u8>> for OutgoingPacket {
   }
 }
 
+#[derive(Clone)]
 pub struct IncomingPacket {
   header: Header,
   payload: Bytes,
@@ -61,25 +158,54 @@ impl IncomingPacket {
     self.payload.to_owned()
   }
 
-  pub fn message_type(&self) -> MessageType {
+  /// Fails with `DecodeError::UnknownMessageType` for types this crate doesn't know.
+  pub fn message_type(&self) -> Result<MessageType, DecodeError> {
+    MessageType::try_from(self.header.message_type)
+      .map_err(|_| DecodeError::UnknownMessageType(self.header.message_type))
+  }
+
+  /// The message type exactly as it appeared on the wire.
+  pub fn raw_message_type(&self) -> u16 {
     self.header.message_type
   }
 
   pub fn target(&self) -> u64 {
     self.header.target
   }
-}
 
-impl TryInto<crate::message::StatePayload> for IncomingPacket {
-  type Error = anyhow::Error;
-  fn try_into(mut self) -> Result<crate::message::StatePayload, Self::Error> {
-    crate::message::StatePayload::deserialize(&mut self.payload)
+  /// Decodes the payload into the message its type says it carries.
+  ///
+  /// Unknown message types decode to `Message::Unknown`; use `Decoders` to handle them.
+  pub fn decode(&self) -> anyhow::Result<Message> {
+    Decoders::default().decode(self)
+  }
+
+  pub fn header(&self) -> &Header {
+    &self.header
+  }
+
+  pub fn source(&self) -> u32 {
+    self.header.source
+  }
+
+  pub fn sequence(&self) -> u8 {
+    self.header.sequence
   }
 }
 
 impl Deserializable for IncomingPacket {
   fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    let actual = bytes.remaining();
     let header = Header::deserialize(bytes)?;
+    if header.size as usize != actual {
+      return Err(
+        DecodeError::SizeMismatch {
+          declared: header.size,
+          actual,
+        }
+        .into(),
+      );
+    }
     let payload = bytes.to_bytes();
     Ok(Self { header, payload })
   }
@@ -101,8 +227,167 @@ mod tests {
     let packet = IncomingPacket::deserialize(&mut bytes).unwrap();
 
     // let packet = Packet::from_bytes(&payload).unwrap();
-    assert_eq!(packet.message_type(), MessageType::SetColor);
+    assert_eq!(packet.message_type(), Ok(MessageType::SetColor));
+  }
+
+  #[test]
+  fn should_decode_message() {
+    let payload = vec![
+      0x31, 0x00, 0x00, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x66, 0x00, 0x00, 0x00, 0x00, 0x55, 0x55, 0xFF, 0xFF, 0xFF, 0xFF, 0xAC, 0x0D,
+      0x00, 0x04, 0x00, 0x00,
+    ];
+
+    let mut bytes = Bytes::from(payload);
+    let packet = IncomingPacket::deserialize(&mut bytes).unwrap();
+
+    match packet.decode().unwrap() {
+      Message::SetColor(payload) => {
+        assert_eq!(payload.color.hue(), 120);
+        assert_eq!(payload.color.kelvin(), 3500);
+        assert_eq!(payload.duration, 1024);
+      }
+      _ => panic!("expected SetColor"),
+    }
+  }
+
+  fn set_color() -> Vec<u8> {
+    vec![
+      0x31, 0x00, 0x00, 0x34, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+      0x00, 0x00, 0x66, 0x00, 0x00, 0x00, 0x00, 0x55, 0x55, 0xFF, 0xFF, 0xFF, 0xFF, 0xAC, 0x0D,
+      0x00, 0x04, 0x00, 0x00,
+    ]
+  }
+
+  fn decode_error(bytes: Vec<u8>) -> DecodeError {
+    let err = IncomingPacket::deserialize(&mut Bytes::from(bytes))
+      .err()
+      .expect("expected an error");
+    *err.downcast_ref::<DecodeError>().unwrap()
+  }
+
+  #[test]
+  fn should_reject_truncated_header() {
+    let mut bytes = set_color();
+    bytes.truncate(20);
+    assert_eq!(
+      decode_error(bytes),
+      DecodeError::Truncated {
+        needed: 36,
+        remaining: 20
+      }
+    );
+  }
+
+  #[test]
+  fn should_reject_size_mismatch() {
+    let mut bytes = set_color();
+    bytes.truncate(45);
+    assert_eq!(
+      decode_error(bytes),
+      DecodeError::SizeMismatch {
+        declared: 49,
+        actual: 45
+      }
+    );
+  }
+
+  #[test]
+  fn should_keep_unknown_message_type() {
+    let mut bytes = set_color();
+    bytes[32] = 0xff;
+    bytes[33] = 0xff;
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    assert_eq!(
+      packet.message_type(),
+      Err(DecodeError::UnknownMessageType(0xffff))
+    );
+    assert_eq!(packet.raw_message_type(), 0xffff);
+  }
+
+  #[test]
+  fn should_reject_truncated_payload() {
+    let mut bytes = set_color();
+    // a consistent header around a payload too short for SetColor
+    bytes.truncate(40);
+    bytes[0] = 40;
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    let err = packet.decode().err().expect("expected an error");
+    assert_eq!(
+      *err.downcast_ref::<DecodeError>().unwrap(),
+      DecodeError::Truncated {
+        needed: 13,
+        remaining: 4
+      }
+    );
+  }
+
+  #[test]
+  fn should_expose_header() {
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(set_color())).unwrap();
+    let header = packet.header();
+    assert_eq!(header.size(), 49);
+    assert_eq!(header.protocol(), 1024);
+    assert!(header.addressable());
+    assert!(header.tagged());
+    assert_eq!(header.source(), 0);
+    assert_eq!(header.target(), 0);
+    assert!(!header.ack_required());
+    assert!(!header.res_required());
+    assert_eq!(header.sequence(), 0);
+    assert_eq!(header.message_type(), 102);
+  }
+
+  #[test]
+  fn should_keep_protocol_from_the_wire() {
+    let mut bytes = set_color();
+    bytes[2] = 0x05;
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    let header = packet.header();
+    assert_eq!(header.protocol(), 1029);
+    assert!(header.addressable());
+    assert!(header.tagged());
+  }
+
+  #[test]
+  fn should_build_with_explicit_flags() {
+    let packet = OutgoingPacket::builder(MessageType::GetService)
+      .sequence(7)
+      .source(1337)
+      .target(0xd073d5)
+      .tagged(true)
+      .addressable(false)
+      .res_required(true)
+      .build(crate::message::EmptyPayload {})
+      .unwrap();
+    let bytes: Vec<u8> = packet.try_into().unwrap();
+
+    let packet = IncomingPacket::deserialize(&mut Bytes::from(bytes)).unwrap();
+    let header = packet.header();
+    assert_eq!(header.size(), 36);
+    assert!(header.tagged());
+    assert!(!header.addressable());
+    assert_eq!(header.target(), 0xd073d5);
+    assert_eq!(header.source(), 1337);
+    assert_eq!(header.sequence(), 7);
+    assert!(header.res_required());
+    assert!(!header.ack_required());
+    assert_eq!(packet.message_type(), Ok(MessageType::GetService));
+  }
+
+  #[test]
+  fn should_tag_untargeted_packets_by_default() {
+    let packet = OutgoingPacket::builder(MessageType::GetService)
+      .build(crate::message::EmptyPayload {})
+      .unwrap();
+    assert!(packet.header().tagged());
 
-    println!("{:#x?}", packet.payload().to_vec());
+    let packet = OutgoingPacket::builder(MessageType::GetService)
+      .target(0xd073d5)
+      .build(crate::message::EmptyPayload {})
+      .unwrap();
+    assert!(!packet.header().tagged());
   }
 }
@@ -0,0 +1,76 @@
+use crate::message::{GetRPowerPayload, SetRPowerPayload, StateRPowerPayload};
+use crate::proto::serialize::ensure_remaining;
+use crate::proto::{Deserializable, Power, Serializable};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::convert::TryFrom;
+
+impl Serializable for GetRPowerPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.relay_index);
+    Ok(())
+  }
+}
+
+impl Deserializable for GetRPowerPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 1)?;
+    let relay_index = bytes.get_u8();
+    Ok(Self { relay_index })
+  }
+}
+
+impl Serializable for SetRPowerPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.relay_index);
+    bytes.put_u16_le(self.level.into());
+    Ok(())
+  }
+}
+
+impl Deserializable for SetRPowerPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 3)?;
+    let relay_index = bytes.get_u8();
+    let level = Power::try_from(bytes.get_u16_le())?;
+    Ok(Self { relay_index, level })
+  }
+}
+
+impl Serializable for StateRPowerPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.relay_index);
+    bytes.put_u16_le(self.level.into());
+    Ok(())
+  }
+}
+
+impl Deserializable for StateRPowerPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 3)?;
+    let relay_index = bytes.get_u8();
+    let level = Power::try_from(bytes.get_u16_le())?;
+    Ok(Self { relay_index, level })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn test_set_rpower_serialize() {
+    let payload = SetRPowerPayload {
+      relay_index: 2,
+      level: Power::On,
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes, vec![0x2, 0xff, 0xff]);
+  }
+  #[test]
+  fn test_state_rpower_deserialize() {
+    let payload: &[u8] = &[0x1, 0x0, 0x0];
+    let deserialized = StateRPowerPayload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(deserialized.relay_index, 1);
+    assert_eq!(deserialized.level, Power::Off);
+  }
+}
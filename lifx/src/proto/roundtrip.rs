@@ -0,0 +1,259 @@
+//! Property tests asserting every payload survives `deserialize(serialize(x))`.
+
+use crate::message::*;
+use crate::proto::{Deserializable, Power, Serializable};
+use bytes::{Buf, BytesMut};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+fn round_trip<T>(payload: T) -> Result<(), TestCaseError>
+where
+  T: Serializable + Deserializable + PartialEq + Debug,
+{
+  let mut bytes = BytesMut::new();
+  payload.serialize(&mut bytes).unwrap();
+  let deserialized = T::deserialize(&mut bytes.to_bytes()).unwrap();
+  prop_assert_eq!(deserialized, payload);
+  Ok(())
+}
+
+/// Floats that compare equal to themselves, NaN never round trips through `==`.
+fn float() -> impl Strategy<Value = f32> {
+  -1.0e6_f32..1.0e6_f32
+}
+
+fn color() -> impl Strategy<Value = Color> {
+  any::<(u16, u16, u16, u16)>().prop_map(|(hue, saturation, brightness, kelvin)| Color {
+    hue,
+    saturation,
+    brightness,
+    kelvin,
+  })
+}
+
+fn colors(size: usize) -> impl Strategy<Value = Vec<Color>> {
+  vec(color(), size)
+}
+
+fn label() -> impl Strategy<Value = Label> {
+  any::<String>().prop_map(|label| Label::new(&label))
+}
+
+fn uuid() -> impl Strategy<Value = LifxUuid> {
+  any::<[u8; 16]>().prop_map(LifxUuid::from_bytes)
+}
+
+fn power() -> impl Strategy<Value = Power> {
+  prop_oneof![Just(Power::On), Just(Power::Off)]
+}
+
+fn waveform() -> impl Strategy<Value = Waveform> {
+  any::<u8>().prop_filter_map("waveform", |value| Waveform::try_from(value).ok())
+}
+
+fn apply() -> impl Strategy<Value = ApplicationRequest> {
+  any::<u8>().prop_filter_map("application request", |value| {
+    ApplicationRequest::try_from(value).ok()
+  })
+}
+
+fn tile() -> impl Strategy<Value = Tile> {
+  (
+    any::<(i16, i16, i16)>(),
+    (float(), float()),
+    any::<(u8, u8, u32, u32)>(),
+    any::<(u64, u16, u16)>(),
+  )
+    .prop_map(
+      |(
+        (accel_meas_x, accel_meas_y, accel_meas_z),
+        (user_x, user_y),
+        (width, height, device_version_vendor, device_version_product),
+        (firmware_build, firmware_version_minor, firmware_version_major),
+      )| Tile {
+        accel_meas_x,
+        accel_meas_y,
+        accel_meas_z,
+        user_x,
+        user_y,
+        width,
+        height,
+        device_version_vendor,
+        device_version_product,
+        firmware_build,
+        firmware_version_minor,
+        firmware_version_major,
+      },
+    )
+}
+
+fn multizone_effect() -> impl Strategy<Value = MultiZoneEffect> {
+  prop_oneof![
+    Just(MultiZoneEffect::Off),
+    Just(MultiZoneEffect::Move(MoveDirection::Left)),
+    Just(MultiZoneEffect::Move(MoveDirection::Right)),
+  ]
+}
+
+fn tile_effect() -> impl Strategy<Value = TileEffect> {
+  let sky = (
+    any::<u8>().prop_filter_map("sky type", |value| SkyType::try_from(value).ok()),
+    any::<(u8, u8)>(),
+  )
+    .prop_map(
+      |(sky_type, (cloud_saturation_min, cloud_saturation_max))| TileEffect::Sky {
+        sky_type,
+        cloud_saturation_min,
+        cloud_saturation_max,
+      },
+    );
+  prop_oneof![
+    Just(TileEffect::Off),
+    Just(TileEffect::Morph),
+    Just(TileEffect::Flame),
+    sky,
+  ]
+}
+
+fn tile_effect_payload() -> impl Strategy<Value = TileEffectPayload> {
+  (
+    any::<(u32, u32, u64)>(),
+    tile_effect(),
+    vec(color(), 0..=PALETTE_COLORS),
+  )
+    .prop_map(
+      |((instance_id, speed, duration), effect, palette)| TileEffectPayload {
+        instance_id,
+        effect,
+        speed,
+        duration,
+        palette,
+      },
+    )
+}
+
+proptest! {
+  #[test]
+  fn device_payloads_round_trip(
+    (service, port) in any::<(u8, u32)>(),
+    (signal, tx, rx) in (float(), any::<u32>(), any::<u32>()),
+    (build, version_minor, version_major) in any::<(u64, u16, u16)>(),
+    (vendor, product, version) in any::<(u32, u32, u32)>(),
+    (time, uptime, downtime) in any::<(u64, u64, u64)>(),
+    (id, label, updated_at) in (uuid(), label(), any::<u64>()),
+    echo in vec(any::<u8>(), 64),
+  ) {
+    round_trip(EmptyPayload {})?;
+    round_trip(StateServicePayload { service, port })?;
+    round_trip(StateHostInfoPayload { signal, tx, rx })?;
+    round_trip(StateWifiInfoPayload { signal, tx, rx })?;
+    round_trip(FirmwarePayload { build, version_minor, version_major })?;
+    round_trip(StateVersionPayload { vendor, product, version })?;
+    round_trip(StateInfoPayload { time, uptime, downtime })?;
+    round_trip(LabelPayload { label: label.clone() })?;
+    round_trip(LocationPayload { location: id, label: label.clone(), updated_at })?;
+    round_trip(GroupPayload { group: id, label, updated_at })?;
+    let mut payload = [0_u8; 64];
+    payload.copy_from_slice(&echo);
+    round_trip(EchoPayload { payload })?;
+  }
+
+  #[test]
+  fn light_payloads_round_trip(
+    color in color(),
+    (duration, period, power, cycles) in (any::<u32>(), any::<u32>(), any::<u16>(), float()),
+    (transient, skew_ratio, waveform) in (any::<bool>(), any::<i16>(), waveform()),
+    set in any::<(bool, bool, bool, bool)>(),
+    label in label(),
+    level in power(),
+  ) {
+    round_trip(SetColorPayload { color, duration })?;
+    round_trip(SetWaveformPayload { transient, color, period, cycles, skew_ratio, waveform })?;
+    round_trip(SetWaveformOptionalPayload {
+      transient,
+      color,
+      period,
+      cycles,
+      skew_ratio,
+      waveform,
+      set_hue: set.0,
+      set_saturation: set.1,
+      set_brightness: set.2,
+      set_kelvin: set.3,
+    })?;
+    round_trip(StatePayload { color, power, label })?;
+    round_trip(SetPowerPayload { level, duration })?;
+    round_trip(StatePowerPayload { level })?;
+    round_trip(InfraredPayload { brightness: power })?;
+  }
+
+  #[test]
+  fn hev_payloads_round_trip(
+    (enable, duration_s, remaining_s) in any::<(bool, u32, u32)>(),
+    result in any::<u8>().prop_filter_map("result", |value| LastHevCycleResult::try_from(value).ok()),
+  ) {
+    round_trip(SetHevCyclePayload { enable, duration_s })?;
+    round_trip(StateHevCyclePayload { duration_s, remaining_s, last_power: enable })?;
+    round_trip(HevCycleConfigurationPayload { indication: enable, duration_s })?;
+    round_trip(StateLastHevCycleResultPayload { result })?;
+  }
+
+  #[test]
+  fn multizone_payloads_round_trip(
+    (start_index, end_index, duration) in any::<(u8, u8, u32)>(),
+    (zones_count, zone_index) in any::<(u16, u16)>(),
+    color in color(),
+    apply in apply(),
+    multi in colors(MULTI_ZONES),
+    extended in vec(color(), 0..=EXTENDED_ZONES),
+  ) {
+    round_trip(SetColorZonesPayload { start_index, end_index, color, duration, apply })?;
+    round_trip(GetColorZonesPayload { start_index, end_index })?;
+    round_trip(StateZonePayload { zones_count: start_index, zone_index: end_index, color })?;
+    let mut colors = [color; MULTI_ZONES];
+    colors.copy_from_slice(&multi);
+    round_trip(StateMultiZonePayload { zones_count: start_index, zone_index: end_index, colors })?;
+    round_trip(SetExtendedColorZonesPayload {
+      duration,
+      apply,
+      zone_index,
+      colors: extended.clone(),
+    })?;
+    round_trip(StateExtendedColorZonesPayload { zones_count, zone_index, colors: extended })?;
+  }
+
+  #[test]
+  fn tile_payloads_round_trip(
+    (start_index, tile_devices) in (any::<u8>(), vec(tile(), 0..=TILE_DEVICES)),
+    (tile_index, length, x, y, width, duration) in any::<(u8, u8, u8, u8, u8, u32)>(),
+    (user_x, user_y) in (float(), float()),
+    colors in colors(TILE_COLORS),
+  ) {
+    round_trip(StateDeviceChainPayload { start_index, tile_devices })?;
+    round_trip(SetUserPositionPayload { tile_index, user_x, user_y })?;
+    round_trip(Get64Payload { tile_index, length, x, y, width })?;
+    round_trip(State64Payload { tile_index, x, y, width, colors: colors.clone() })?;
+    round_trip(Set64Payload { tile_index, length, x, y, width, duration, colors })?;
+  }
+
+  #[test]
+  fn effect_payloads_round_trip(
+    (instance_id, speed, duration) in any::<(u32, u32, u64)>(),
+    effect in multizone_effect(),
+    tile in tile_effect_payload(),
+  ) {
+    round_trip(MultiZoneEffectPayload { instance_id, effect, speed, duration })?;
+    round_trip(GetTileEffectPayload {})?;
+    round_trip(SetTileEffectPayload(tile.clone()))?;
+    round_trip(StateTileEffectPayload(tile))?;
+  }
+
+  #[test]
+  fn relay_payloads_round_trip(relay_index in any::<u8>(), level in power()) {
+    round_trip(GetRPowerPayload { relay_index })?;
+    round_trip(SetRPowerPayload { relay_index, level })?;
+    round_trip(StateRPowerPayload { relay_index, level })?;
+  }
+}
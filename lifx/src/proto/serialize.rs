@@ -1,4 +1,5 @@
-use bytes::{Bytes, BytesMut};
+use crate::error::DecodeError;
+use bytes::{Buf, Bytes, BytesMut};
 
 pub trait Serializable {
   fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()>;
@@ -9,3 +10,12 @@ pub trait Deserializable {
   where
     Self: Sized;
 }
+
+/// Fails unless `bytes` still holds the `needed` bytes about to be read.
+pub(crate) fn ensure_remaining(bytes: &Bytes, needed: usize) -> Result<(), DecodeError> {
+  let remaining = bytes.remaining();
+  if remaining < needed {
+    return Err(DecodeError::Truncated { needed, remaining });
+  }
+  Ok(())
+}
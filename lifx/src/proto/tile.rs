@@ -0,0 +1,381 @@
+use crate::message::{
+  Color, Get64Payload, Set64Payload, SetUserPositionPayload, State64Payload,
+  StateDeviceChainPayload, Tile, TILE_COLORS, TILE_DEVICES,
+};
+use crate::proto::color::BLANK;
+use crate::proto::serialize::ensure_remaining;
+use crate::proto::{Deserializable, Serializable};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+
+/// Fills the unused slots of a device chain.
+const BLANK_TILE: Tile = Tile {
+  accel_meas_x: 0,
+  accel_meas_y: 0,
+  accel_meas_z: 0,
+  user_x: 0.0,
+  user_y: 0.0,
+  width: 0,
+  height: 0,
+  device_version_vendor: 0,
+  device_version_product: 0,
+  firmware_build: 0,
+  firmware_version_minor: 0,
+  firmware_version_major: 0,
+};
+
+fn serialize_colors(colors: &[Color], bytes: &mut BytesMut) -> anyhow::Result<()> {
+  if colors.len() > TILE_COLORS {
+    return Err(anyhow::Error::msg(format!(
+      "Unable to send {} colors, at most {} fit on a tile",
+      colors.len(),
+      TILE_COLORS
+    )));
+  }
+  for color in colors {
+    color.serialize(bytes)?;
+  }
+  for _ in colors.len()..TILE_COLORS {
+    BLANK.serialize(bytes)?;
+  }
+  Ok(())
+}
+
+fn deserialize_colors(bytes: &mut Bytes) -> anyhow::Result<Vec<Color>> {
+  ensure_remaining(bytes, TILE_COLORS * 8)?;
+  let mut colors = Vec::with_capacity(TILE_COLORS);
+  for _ in 0..TILE_COLORS {
+    colors.push(Color::deserialize(bytes)?);
+  }
+  Ok(colors)
+}
+
+impl Serializable for Tile {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_i16_le(self.accel_meas_x);
+    bytes.put_i16_le(self.accel_meas_y);
+    bytes.put_i16_le(self.accel_meas_z);
+    // reserve 2 bytes
+    bytes.put_i16_le(0);
+    bytes.put_f32_le(self.user_x);
+    bytes.put_f32_le(self.user_y);
+    bytes.put_u8(self.width);
+    bytes.put_u8(self.height);
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u32_le(self.device_version_vendor);
+    bytes.put_u32_le(self.device_version_product);
+    // reserve 4 bytes
+    bytes.put_u32_le(0);
+    bytes.put_u64_le(self.firmware_build);
+    // reserve 8 bytes
+    bytes.put_u64_le(0);
+    bytes.put_u16_le(self.firmware_version_minor);
+    bytes.put_u16_le(self.firmware_version_major);
+    // reserve 4 bytes
+    bytes.put_u32_le(0);
+    Ok(())
+  }
+}
+
+impl Deserializable for Tile {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 55)?;
+    let accel_meas_x = bytes.get_i16_le();
+    let accel_meas_y = bytes.get_i16_le();
+    let accel_meas_z = bytes.get_i16_le();
+    // skip 2 bytes
+    bytes.advance(2);
+    let user_x = bytes.get_f32_le();
+    let user_y = bytes.get_f32_le();
+    let width = bytes.get_u8();
+    let height = bytes.get_u8();
+    // skip u8
+    bytes.advance(1);
+    let device_version_vendor = bytes.get_u32_le();
+    let device_version_product = bytes.get_u32_le();
+    // skip 4 bytes
+    bytes.advance(4);
+    let firmware_build = bytes.get_u64_le();
+    // skip 8 bytes
+    bytes.advance(8);
+    let firmware_version_minor = bytes.get_u16_le();
+    let firmware_version_major = bytes.get_u16_le();
+    // skip 4 bytes
+    bytes.advance(4);
+    Ok(Self {
+      accel_meas_x,
+      accel_meas_y,
+      accel_meas_z,
+      user_x,
+      user_y,
+      width,
+      height,
+      device_version_vendor,
+      device_version_product,
+      firmware_build,
+      firmware_version_minor,
+      firmware_version_major,
+    })
+  }
+}
+
+impl Serializable for StateDeviceChainPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    if self.tile_devices.len() > TILE_DEVICES {
+      return Err(anyhow::Error::msg(format!(
+        "Unable to send {} tiles, at most {} fit in a chain",
+        self.tile_devices.len(),
+        TILE_DEVICES
+      )));
+    }
+    bytes.put_u8(self.start_index);
+    for tile in self.tile_devices.iter() {
+      tile.serialize(bytes)?;
+    }
+    for _ in self.tile_devices.len()..TILE_DEVICES {
+      BLANK_TILE.serialize(bytes)?;
+    }
+    bytes.put_u8(self.tile_devices.len() as u8);
+    Ok(())
+  }
+}
+
+impl Deserializable for StateDeviceChainPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 882)?;
+    let start_index = bytes.get_u8();
+    let mut tile_devices = Vec::with_capacity(TILE_DEVICES);
+    for _ in 0..TILE_DEVICES {
+      tile_devices.push(Tile::deserialize(bytes)?);
+    }
+    let tile_devices_count = bytes.get_u8();
+    tile_devices.truncate(tile_devices_count as usize);
+    Ok(Self {
+      start_index,
+      tile_devices,
+    })
+  }
+}
+
+impl Serializable for SetUserPositionPayload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.tile_index);
+    // reserve 2 bytes
+    bytes.put_u16_le(0);
+    bytes.put_f32_le(self.user_x);
+    bytes.put_f32_le(self.user_y);
+    Ok(())
+  }
+}
+
+impl Deserializable for SetUserPositionPayload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 11)?;
+    let tile_index = bytes.get_u8();
+    // skip 2 bytes
+    bytes.advance(2);
+    let user_x = bytes.get_f32_le();
+    let user_y = bytes.get_f32_le();
+    Ok(Self {
+      tile_index,
+      user_x,
+      user_y,
+    })
+  }
+}
+
+impl Serializable for Get64Payload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.tile_index);
+    bytes.put_u8(self.length);
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u8(self.x);
+    bytes.put_u8(self.y);
+    bytes.put_u8(self.width);
+    Ok(())
+  }
+}
+
+impl Deserializable for Get64Payload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 6)?;
+    let tile_index = bytes.get_u8();
+    let length = bytes.get_u8();
+    // skip u8
+    bytes.advance(1);
+    let x = bytes.get_u8();
+    let y = bytes.get_u8();
+    let width = bytes.get_u8();
+    Ok(Self {
+      tile_index,
+      length,
+      x,
+      y,
+      width,
+    })
+  }
+}
+
+impl Serializable for State64Payload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.tile_index);
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u8(self.x);
+    bytes.put_u8(self.y);
+    bytes.put_u8(self.width);
+    serialize_colors(&self.colors, bytes)
+  }
+}
+
+impl Deserializable for State64Payload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 5)?;
+    let tile_index = bytes.get_u8();
+    // skip u8
+    bytes.advance(1);
+    let x = bytes.get_u8();
+    let y = bytes.get_u8();
+    let width = bytes.get_u8();
+    let colors = deserialize_colors(bytes)?;
+    Ok(Self {
+      tile_index,
+      x,
+      y,
+      width,
+      colors,
+    })
+  }
+}
+
+impl Serializable for Set64Payload {
+  fn serialize(&self, bytes: &mut BytesMut) -> anyhow::Result<()> {
+    bytes.put_u8(self.tile_index);
+    bytes.put_u8(self.length);
+    // reserve u8
+    bytes.put_u8(0);
+    bytes.put_u8(self.x);
+    bytes.put_u8(self.y);
+    bytes.put_u8(self.width);
+    bytes.put_u32_le(self.duration);
+    serialize_colors(&self.colors, bytes)
+  }
+}
+
+impl Deserializable for Set64Payload {
+  fn deserialize(bytes: &mut Bytes) -> anyhow::Result<Self> {
+    ensure_remaining(bytes, 10)?;
+    let tile_index = bytes.get_u8();
+    let length = bytes.get_u8();
+    // skip u8
+    bytes.advance(1);
+    let x = bytes.get_u8();
+    let y = bytes.get_u8();
+    let width = bytes.get_u8();
+    let duration = bytes.get_u32_le();
+    let colors = deserialize_colors(bytes)?;
+    Ok(Self {
+      tile_index,
+      length,
+      x,
+      y,
+      width,
+      duration,
+      colors,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tile_round_trip() {
+    let tile = Tile {
+      accel_meas_x: -12,
+      accel_meas_y: 7,
+      accel_meas_z: 1024,
+      user_x: 1.5,
+      user_y: -0.5,
+      width: 8,
+      height: 8,
+      device_version_vendor: 1,
+      device_version_product: 55,
+      firmware_build: 1548977726000000000,
+      firmware_version_minor: 50,
+      firmware_version_major: 3,
+    };
+    let mut bytes = BytesMut::new();
+    tile.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 55);
+    let deserialized = Tile::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized, tile);
+  }
+  #[test]
+  fn test_state_device_chain_deserialize() {
+    let mut bytes = BytesMut::new();
+    bytes.put_u8(0);
+    let tile = Tile {
+      width: 8,
+      height: 8,
+      ..BLANK_TILE
+    };
+    for _ in 0..TILE_DEVICES {
+      tile.serialize(&mut bytes).unwrap();
+    }
+    bytes.put_u8(5);
+    let deserialized = StateDeviceChainPayload::deserialize(&mut bytes.to_bytes()).unwrap();
+    assert_eq!(deserialized.start_index, 0);
+    assert_eq!(deserialized.tile_devices, vec![tile; 5]);
+  }
+  #[test]
+  fn test_set64_serialize() {
+    let payload = Set64Payload {
+      tile_index: 2,
+      length: 1,
+      x: 0,
+      y: 0,
+      width: 8,
+      duration: 1024,
+      colors: vec![Color::new(120, 100, 100, 3500)],
+    };
+    let mut bytes = BytesMut::new();
+    payload.serialize(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 10 + TILE_COLORS * 8);
+    assert_eq!(
+      &bytes[0..10],
+      &[0x2, 0x1, 0x0, 0x0, 0x0, 0x8, 0x0, 0x4, 0x0, 0x0]
+    );
+    assert_eq!(
+      &bytes[10..18],
+      &[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]
+    );
+    assert!(bytes[18..].iter().all(|byte| *byte == 0));
+  }
+  #[test]
+  fn test_set64_rejects_too_many_colors() {
+    let payload = Set64Payload {
+      tile_index: 0,
+      length: 1,
+      x: 0,
+      y: 0,
+      width: 8,
+      duration: 0,
+      colors: vec![BLANK; TILE_COLORS + 1],
+    };
+    assert!(payload.serialize(&mut BytesMut::new()).is_err());
+  }
+  #[test]
+  fn test_state64_deserialize() {
+    let mut payload = vec![0x1, 0x0, 0x0, 0x0, 0x8];
+    payload.resize(5 + TILE_COLORS * 8, 0);
+    payload[5..13].copy_from_slice(&[0x55, 0x55, 0xff, 0xff, 0xff, 0xff, 0xac, 0xd]);
+    let deserialized = State64Payload::deserialize(&mut Bytes::from(payload)).unwrap();
+    assert_eq!(deserialized.tile_index, 1);
+    assert_eq!(deserialized.width, 8);
+    assert_eq!(deserialized.colors.len(), TILE_COLORS);
+    assert_eq!(deserialized.colors[0], Color::new(120, 100, 100, 3500));
+  }
+}
@@ -4,6 +4,9 @@ use log::trace;
 use std::net::SocketAddr;
 use tokio::net::udp::RecvHalf;
 
+/// Large enough for the biggest message, a tile device chain.
+const BUFFER_SIZE: usize = 1024;
+
 pub struct Reader {
   recv_half: RecvHalf,
 }
@@ -14,7 +17,7 @@ impl Reader {
   }
 
   pub async fn read_packet(&mut self) -> anyhow::Result<(SocketAddr, IncomingPacket)> {
-    let mut buf = [0; 256];
+    let mut buf = [0; BUFFER_SIZE];
     let (amt, addr) = self.recv_half.recv_from(&mut buf).await?;
     let read = &buf[0..amt];
     trace!("addr: {}, read {:x?}", addr, read);
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+/// How often and for how long a request is resent while waiting for its reply.
+///
+/// Each attempt waits twice as long as the previous one, starting at `backoff`,
+/// and no attempt is made once `deadline` has passed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RetryPolicy {
+  pub attempts: u32,
+  pub backoff: Duration,
+  pub deadline: Duration,
+}
+
+impl RetryPolicy {
+  pub fn new(attempts: u32, backoff: Duration, deadline: Duration) -> Self {
+    Self {
+      attempts,
+      backoff,
+      deadline,
+    }
+  }
+
+  /// How long to wait for a reply after sending the given attempt, starting at zero.
+  pub fn wait(&self, attempt: u32) -> Duration {
+    let factor = 2_u32.saturating_pow(attempt);
+    let wait = self.backoff.checked_mul(factor).unwrap_or(self.deadline);
+    wait.min(self.deadline)
+  }
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self::new(5, Duration::from_millis(100), Duration::from_secs(3))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_double_wait_per_attempt() {
+    let policy = RetryPolicy::new(3, Duration::from_millis(100), Duration::from_secs(1));
+    assert_eq!(policy.wait(0), Duration::from_millis(100));
+    assert_eq!(policy.wait(1), Duration::from_millis(200));
+    assert_eq!(policy.wait(2), Duration::from_millis(400));
+  }
+  #[test]
+  fn should_not_overflow_wait() {
+    let policy = RetryPolicy::new(100, Duration::from_secs(1), Duration::from_secs(10));
+    assert_eq!(policy.wait(64), Duration::from_secs(10));
+  }
+}
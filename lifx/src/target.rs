@@ -0,0 +1,83 @@
+use std::fmt;
+
+/// Which devices a message is addressed to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+  /// Every device on the network.
+  All,
+  /// A single device, identified by the MAC address it reports as its serial.
+  Serial([u8; 6]),
+}
+
+impl Target {
+  pub fn serial(&self) -> Option<[u8; 6]> {
+    match self {
+      Target::All => None,
+      Target::Serial(serial) => Some(*serial),
+    }
+  }
+}
+
+impl From<u64> for Target {
+  fn from(target: u64) -> Self {
+    let bytes = target.to_le_bytes();
+    let mut serial = [0_u8; 6];
+    serial.copy_from_slice(&bytes[0..6]);
+    if serial == [0; 6] {
+      Target::All
+    } else {
+      Target::Serial(serial)
+    }
+  }
+}
+
+impl From<Target> for u64 {
+  fn from(target: Target) -> Self {
+    match target {
+      Target::All => 0,
+      Target::Serial(serial) => {
+        let mut bytes = [0_u8; 8];
+        bytes[0..6].copy_from_slice(&serial);
+        u64::from_le_bytes(bytes)
+      }
+    }
+  }
+}
+
+impl fmt::Display for Target {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Target::All => write!(f, "all"),
+      Target::Serial(serial) => write!(
+        f,
+        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+        serial[0], serial[1], serial[2], serial[3], serial[4], serial[5]
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  #[test]
+  fn should_convert_serial_to_wire_target() {
+    let target = Target::Serial([0xd0, 0x73, 0xd5, 0x01, 0x02, 0x03]);
+    let raw: u64 = target.into();
+    assert_eq!(
+      raw.to_le_bytes(),
+      [0xd0, 0x73, 0xd5, 0x01, 0x02, 0x03, 0, 0]
+    );
+    assert_eq!(Target::from(raw), target);
+  }
+  #[test]
+  fn should_treat_zero_as_all() {
+    assert_eq!(Target::from(0), Target::All);
+    assert_eq!(u64::from(Target::All), 0);
+  }
+  #[test]
+  fn should_display_serial() {
+    let target = Target::Serial([0xd0, 0x73, 0xd5, 0x01, 0x02, 0x03]);
+    assert_eq!(target.to_string(), "d0:73:d5:01:02:03");
+  }
+}
@@ -18,7 +18,7 @@ impl Writer {
     packet: OutgoingPacket,
   ) -> anyhow::Result<()> {
     let bytes: Vec<u8> = packet.try_into()?;
-    self.send_half.send_to(&bytes, &addr).await?;
+    self.send_half.send_to(&bytes, addr).await?;
     Ok(())
   }
 }
@@ -1,3 +1,6 @@
+// diesel 1.x macros expand to impls that newer compilers flag as non-local
+#![allow(non_local_definitions)]
+
 #[macro_use]
 extern crate diesel;
 #[macro_use]